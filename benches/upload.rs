@@ -0,0 +1,101 @@
+//! Compares uploading the render area to the window's frame buffer
+//! by copying `to_vec()` (what the game used to do) with
+//! `Game::present`, for a 256x96 world. Run it with
+//! `cargo bench --bench upload`. The last case also draws a moving
+//! sprite, `Game::image_at` allocates the Vec it returns.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use pixel_game_lib::{
+    color::Color,
+    game::{GameBuilder, WORLD_LAYER},
+    mat::{Mat, MatSlice},
+    vec2::Vec2,
+};
+
+/// Counts allocations so the upload paths can be checked for them.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const WORLD_DIMS: Vec2 = Vec2(256., 96.);
+const RENDER_DIMS: Vec2 = Vec2(128., 48.);
+const FRAMES: usize = 2000;
+
+/// Run `f` FRAMES times, print the time and the allocations per
+/// frame.
+fn bench(name: &str, mut f: impl FnMut()) {
+    // Warm up, buffers are allocated on the first frames.
+    for _ in 0..10 {
+        f();
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{:<24} {:>10.2?}/frame {:>6} allocations/frame",
+        name,
+        elapsed / FRAMES as u32,
+        allocations / FRAMES
+    );
+}
+
+fn main() {
+    let mut world = Mat::filled_with(Color::BLACK, WORLD_DIMS.to_usize());
+    world.fill_with_r(|(x, y)| Color::new(x as u8, y as u8, (x ^ y) as u8, 255));
+    let mut pixels = vec![0; RENDER_DIMS.to_usize().0 * RENDER_DIMS.to_usize().1 * 4];
+
+    bench("to_vec", || {
+        let render_area = world.slice((64, 24), RENDER_DIMS.to_usize(), (false, false));
+        for (c, pix) in render_area.to_vec().iter().zip(pixels.chunks_exact_mut(4)) {
+            pix.copy_from_slice(&c.to_bytes());
+        }
+        black_box(&mut pixels);
+    });
+
+    let mut game = GameBuilder::new()
+        .dims(WORLD_DIMS)
+        .render_dims(RENDER_DIMS)
+        .render_pos(Vec2(64., 24.))
+        .build();
+    *game.layer_mut(WORLD_LAYER).unwrap().mat_mut() = world.clone();
+
+    bench("present (full)", || {
+        // Marks the whole layer as changed.
+        game.layer_mut(WORLD_LAYER).unwrap().mat_mut();
+        game.present(&mut pixels);
+        black_box(&mut pixels);
+    });
+
+    let sprite = Mat::filled_with(Color::WHITE, (16, 16));
+    let mut x = 0.;
+    bench("sprite + present", || {
+        game.clear_dirty();
+        x = (x + 1.) % 100.;
+        game.image_at(Vec2(64. + x, 40.), &sprite);
+        game.present(&mut pixels);
+        black_box(&mut pixels);
+    });
+}
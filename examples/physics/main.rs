@@ -8,7 +8,7 @@ use pixel_game_lib::{
     game::GameBuilder,
     mat::MatSlice,
     object::Object,
-    physics::Physics,
+    physics::{Integrator, Physics},
     vec2::Vec2,
 };
 use winit::event::VirtualKeyCode;
@@ -36,48 +36,32 @@ fn main() {
     let mut animation: Drawable = UniqueFrame::from_color(Color::WHITE, (1, 1)).into();
     let image = animation.next().unwrap();
 
+    // The weight is the only persistent force, the keys push the
+    // object during the updates they are held.
     physics.set_tf_to_w();
+    *physics.integrator_mut() = Integrator::VelocityVerlet;
+    *physics.linear_drag_mut() = 20.;
+    *physics.max_speed_mut() = Vec2(80., 120.);
 
     let total_time = time::Instant::now();
-    let mut timer = total_time.clone();
-
-    let mut forces_applied = (false, false, false);
 
     let w = physics.w();
 
-    game.run(move |game| {
+    game.run(move |game, dt| {
         if game.input().key_held(VirtualKeyCode::Left) {
-            if !forces_applied.0 {
-                physics.apply_force(Vec2(-2000., 0.));
-                forces_applied.0 = true;
-            }
-        } else if forces_applied.0 {
-            physics.apply_force(Vec2(2000., 0.));
-            forces_applied.0 = false;
+            physics.apply_force_once(Vec2(-2000., 0.));
         }
-
         if game.input().key_held(VirtualKeyCode::Right) {
-            if !forces_applied.1 {
-                physics.apply_force(Vec2(2000., 0.));
-                forces_applied.1 = true;
-            }
-        } else if forces_applied.1 {
-            physics.apply_force(Vec2(-2000., 0.));
-            forces_applied.1 = false;
+            physics.apply_force_once(Vec2(2000., 0.));
         }
-
         if game.input().key_held(VirtualKeyCode::Up) {
-            if !forces_applied.2 {
-                physics.apply_force(Vec2(0., -w - 6000.));
-                forces_applied.2 = true;
-            }
-        } else if forces_applied.2 {
-            physics.apply_force(Vec2(0., w + 6000.));
-            forces_applied.2 = false;
+            physics.apply_force_once(Vec2(0., -w - 6000.));
+        }
+        if game.input().key_pressed(VirtualKeyCode::Space) {
+            physics.apply_impulse(Vec2(0., -60. * 40.));
         }
 
-        let t = timer.elapsed().as_secs_f64();
-        physics.update(t);
+        physics.update(dt);
 
         let pos = *physics.pos();
         *object.pos_mut() = pos;
@@ -89,7 +73,5 @@ fn main() {
 
         game.clear(Color::new(0, 0, 0, 0));
         game.image_at(*object.pos(), &image.as_slice());
-
-        timer = time::Instant::now();
     });
 }
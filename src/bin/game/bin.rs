@@ -1,21 +1,26 @@
 #![forbid(unsafe_code)]
 
-use std::time::{self, Instant};
+use std::{path::Path, time::Instant};
 
 use pixel_game_lib::{
+    assets::Handle,
     color::Color,
-    game::GameBuilder,
+    controller::{CharacterController, ControllerInput, ControllerParams, Slope, Terrain},
+    game::{GameBuilder, Layer, Timestep},
+    input::{Binding, InputMap},
     mat::{Mat, MatSlice},
     object::Object,
     physics::Physics,
-    resources::{import_sprite, import_spritesheet},
-    uv_map::UvMap,
+    resources::ResourceError,
     vec2::Vec2,
 };
 use winit::event::VirtualKeyCode;
 
 const DIMS: Vec2 = Vec2(128., 48.);
 const BG_COLOR: Color = Color::new(240, 240, 255, 255);
+const HILLS_COLOR: Color = Color::new(200, 205, 235, 255);
+const GROUND_COLOR: Color = Color::new(40, 40, 50, 255);
+const HITBOX_COLOR: Color = Color::new(255, 0, 80, 255);
 
 const WALKING_SPEED: f64 = 15.;
 
@@ -33,29 +38,109 @@ enum CharacterState {
 struct Animation {
     timer: Instant,
     current_frame: usize,
-    frames: Vec<Mat<Color>>,
+    frames: Handle<Vec<Mat<Color>>>,
 }
 
-fn main() {
-    let game = GameBuilder::new()
+/// Bindings can be changed in this file, see `InputMap`.
+const INPUT_PATH: &str = "input.txt";
+
+fn input_map() -> InputMap {
+    let mut input_map = InputMap::new();
+    for (action, keys) in [
+        ("move_left", [VirtualKeyCode::Left, VirtualKeyCode::A]),
+        ("move_right", [VirtualKeyCode::Right, VirtualKeyCode::D]),
+        ("jump", [VirtualKeyCode::Up, VirtualKeyCode::W]),
+    ] {
+        for key in keys {
+            input_map.bind(action, Binding::Key(key));
+        }
+    }
+    input_map.bind("jump", Binding::Key(VirtualKeyCode::Space));
+    input_map.bind("toggle_hitboxes", Binding::Key(VirtualKeyCode::H));
+
+    if Path::new(INPUT_PATH).exists() {
+        match InputMap::load(INPUT_PATH) {
+            Ok(custom) => {
+                for action in custom.actions() {
+                    input_map.rebind(action, custom.bindings(action));
+                }
+            }
+            Err(e) => eprintln!("{}, using the default bindings", e),
+        }
+    }
+    input_map
+}
+
+fn main() -> Result<(), ResourceError> {
+    let mut game = GameBuilder::new()
         .dims(Vec2(2. * DIMS.0, 2. * DIMS.1))
         .render_dims(Vec2(DIMS.0, DIMS.1))
-        .background_color(BG_COLOR)
+        .timestep(Timestep::fixed(60.))
+        .hot_reload(true)
+        .input_map(input_map())
         .build();
 
+    // Half as fast as the world, so it only needs to be halfway
+    // between the render area and the world in size.
+    let mut background = Layer::new(Vec2(1.5 * DIMS.0, 1.5 * DIMS.1), Some(BG_COLOR));
+    *background.z_index_mut() = -1;
+    *background.parallax_mut() = 0.5;
+    for i in 0..6 {
+        background.painter().fill_circle(
+            Vec2(i as f64 * 36. + 10., 1.5 * DIMS.1 - 8.),
+            20. + (i % 3) as f64 * 6.,
+            HILLS_COLOR,
+        );
+    }
+    game.add_layer("background", background);
+
     let platform1 = Object::new(Vec2(10., 44.), Vec2(40., 4.), None);
     let mut platform2 = platform1.clone();
     platform2.pos_mut().0 = 74.;
-
-    let mut character = Object::new(Vec2(24., 0.), Vec2(8., 18.), Some(Vec2(-8., -2.)));
-
-    let map = UvMap::new(import_sprite("textures/uv_map.png", (24, 24)));
-
-    let ground_img = Mat::filled_with(Color::new(40, 40, 50, 255), platform1.dims().to_usize());
-
-    let standing_img = import_sprite("sprites/standing.png", (24, 24));
-
-    let walking_frames = import_spritesheet("spritesheets/walking.png", (24, 24), (5, 2), 10);
+    let platform3 = Object::new(Vec2(150., 30.), Vec2(60., 4.), None);
+    // Can be jumped through from below.
+    let bridge = Object::new(Vec2(44., 36.), Vec2(36., 1.), None);
+    let slope = Slope::new(Vec2(115., 44.), Vec2(150., 30.));
+
+    // The platforms don't move, they are drawn once on their own
+    // layer so only the character is cleared and drawn every frame.
+    let mut ground = Layer::new(*game.dims(), None);
+    *ground.z_index_mut() = -1;
+    for platform in [&platform1, &platform2, &platform3, &bridge] {
+        ground
+            .painter()
+            .fill_rect(*platform.pos(), *platform.dims(), GROUND_COLOR);
+    }
+    ground.painter().fill_polygon(
+        &[
+            slope.from,
+            slope.to,
+            Vec2(slope.to.0, slope.to.1 + 4.),
+            Vec2(slope.from.0, slope.from.1 + 4.),
+        ],
+        GROUND_COLOR,
+    );
+    game.add_layer("ground", ground);
+
+    let mut character = CharacterController::new(
+        Object::new(Vec2(24., 0.), Vec2(8., 18.), Some(Vec2(-8., -2.))),
+        Physics::new(Vec2::ZERO, Vec2::ZERO, 60., 300.),
+        ControllerParams {
+            walk_speed: WALKING_SPEED,
+            ..Default::default()
+        },
+    );
+
+    *game.camera_mut().dead_zone_mut() = Vec2(24., 16.);
+    *game.camera_mut().smoothing_mut() = 8.;
+
+    let map = game.assets_mut().texture("textures/uv_map.png")?;
+
+    let standing_img = game.assets_mut().sprite("sprites/standing.png")?;
+
+    let walking_frames = game
+        .assets_mut()
+        .spritesheet("spritesheets/walking.png", (24, 24), 10)?;
     let mut walking_anim = Animation {
         timer: Instant::now(),
         current_frame: 0,
@@ -64,69 +149,57 @@ fn main() {
 
     let mut direction = LateralDirection::Right;
 
-    let mut timer = time::Instant::now();
-
-    let mut char_state = CharacterState::Standing;
-
-    let mut physics = Physics::new(*character.pos(), Vec2(0., 0.), 60., 300.);
-    physics.set_tf_to_w();
-
     let start_instant = Instant::now();
     let mut n: u8 = 0;
 
-    let mut prev_grounded = false;
-    game.run(move |game| {
-        let input = game.input();
-
-        let elapsed = timer.elapsed().as_secs_f64();
-        physics.update(elapsed);
-
-        *character.pos_mut() = *physics.pos();
-
-        let grounded = character.in_intersecting_with_any(&[&platform1, &platform2]);
+    let mut show_hitboxes = false;
+    game.run(move |game, dt| {
+        if game.action_pressed("toggle_hitboxes") {
+            show_hitboxes = !show_hitboxes;
+        }
 
-        let lateral_moving_speed = if grounded {
-            WALKING_SPEED
-        } else {
-            WALKING_SPEED * 0.8
+        let mut input = ControllerInput {
+            direction: 0.,
+            jump_pressed: game.action_pressed("jump"),
+            jump_held: game.action_held("jump"),
         };
-        if input.key_held(VirtualKeyCode::Left) {
+        if game.action_held("move_left") {
             direction = LateralDirection::Left;
-            char_state = CharacterState::Walking;
-            physics.v_mut().0 = -lateral_moving_speed;
-        } else if input.key_held(VirtualKeyCode::Right) {
+            input.direction = -1.;
+        } else if game.action_held("move_right") {
             direction = LateralDirection::Right;
-            char_state = CharacterState::Walking;
-            physics.v_mut().0 = lateral_moving_speed;
-        } else {
-            char_state = CharacterState::Standing;
-            physics.v_mut().0 = 0.;
+            input.direction = 1.;
         }
+        character.update(
+            dt,
+            &input,
+            &Terrain {
+                solids: &[&platform1, &platform2, &platform3],
+                one_way: &[&bridge],
+                slopes: &[slope],
+            },
+        );
 
-        if grounded {
-            // character.intersection_boundaries_with(other);
-            if !prev_grounded {
-                physics.a_mut().1 = 0.;
-                physics.v_mut().1 = 0.;
-                *physics.tf_mut() = Vec2::ZERO;
-            }
-
-            if input.key_pressed(VirtualKeyCode::Up) {
-                physics.v_mut().1 = -80.;
-            }
+        let char_state = if !character.grounded() {
+            CharacterState::MidAir
+        } else if input.direction != 0. {
+            CharacterState::Walking
         } else {
-            char_state = CharacterState::MidAir;
-            physics.set_tf_to_w();
+            CharacterState::Standing
+        };
+
+        if character.landed() {
+            game.camera_mut().shake(1., 0.15);
         }
 
-        prev_grounded = grounded;
+        game.camera_mut().follow(character.object());
 
         n = (n + 1) % 6;
         if n == 0 {
             println!("t = {}s", start_instant.elapsed().as_secs_f32());
-            println!("v = {:?}", physics.v());
-            println!("a = {:?}", physics.a());
-            println!("tf = {:?}", physics.tf());
+            println!("v = {:?}", character.physics().v());
+            println!("a = {:?}", character.physics().a());
+            println!("tf = {:?}", character.physics().tf());
         }
 
         let flip = match direction {
@@ -134,30 +207,39 @@ fn main() {
             LateralDirection::Right => false,
         };
 
-        game.clear(BG_COLOR);
-
-        let ground_img_slice = ground_img.as_slice();
-        game.image_at(platform1.image_pos(), &ground_img_slice);
-        game.image_at(platform2.image_pos(), &ground_img_slice);
+        game.clear_dirty();
         game.image_at(
-            character.image_pos(),
+            character.object().image_pos(),
             &match char_state {
-                CharacterState::Standing => map.render(&standing_img.slice_flip((flip, false))),
+                CharacterState::Standing => map
+                    .get()
+                    .render(&standing_img.get().slice_flip((flip, false))),
                 CharacterState::Walking => {
                     if walking_anim.timer.elapsed().as_secs_f64() >= 1.5 / WALKING_SPEED {
                         walking_anim.current_frame =
-                            (walking_anim.current_frame + 1) % walking_anim.frames.len();
+                            (walking_anim.current_frame + 1) % walking_anim.frames.get().len();
                         walking_anim.timer = Instant::now();
                     };
-                    map.render(
-                        &walking_anim.frames[walking_anim.current_frame].slice_flip((flip, false)),
+                    map.get().render(
+                        &walking_anim.frames.get()[walking_anim.current_frame]
+                            .slice_flip((flip, false)),
                     )
                 }
-                CharacterState::MidAir => map.render(&standing_img.slice_flip((flip, false))),
+                CharacterState::MidAir => map
+                    .get()
+                    .render(&standing_img.get().slice_flip((flip, false))),
             }
             .as_slice(),
         );
 
-        timer = time::Instant::now();
+        if show_hitboxes {
+            game.painter().rect(
+                *character.object().pos(),
+                *character.object().dims(),
+                HITBOX_COLOR,
+            );
+        }
     });
+
+    Ok(())
 }
@@ -0,0 +1,428 @@
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    color::Color,
+    mat::Mat,
+    resources::{decode_image, open_image, split_spritesheet, ResourceError, ASSETS_PATH},
+    uv_map::UvMap,
+};
+
+/// A cheap to clone reference to a loaded asset, the asset is
+/// shared by all the clones so a reloaded asset shows up
+/// everywhere.
+pub struct Handle<T>(Rc<RefCell<T>>);
+
+impl<T> Handle<T> {
+    pub fn new(value: T) -> Self {
+        Handle(Rc::new(RefCell::new(value)))
+    }
+
+    /// Borrow the asset, the borrow should not be held across
+    /// updates or the asset can't be reloaded.
+    pub fn get(&self) -> Ref<'_, T> {
+        self.0.borrow()
+    }
+
+    /// Replace the asset for every clone of the handle, return the
+    /// old one.
+    pub fn replace(&self, value: T) -> T {
+        self.0.replace(value)
+    }
+
+    /// Return true if both handles point to the same asset.
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Rc::ptr_eq(&a.0, &b.0)
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle(Rc::clone(&self.0))
+    }
+}
+
+impl<T> Debug for Handle<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Handle").field(&self.0.borrow()).finish()
+    }
+}
+
+/// Hot reloading state, see `Assets::set_hot_reload`.
+struct HotReload {
+    interval: Duration,
+    last_poll: Instant,
+    modified: HashMap<PathBuf, SystemTime>,
+    /// The modification times that failed to reload, each one is
+    /// only reported once.
+    failed: HashMap<PathBuf, SystemTime>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Path, sprite dims and number of sprites of a spritesheet.
+type SpritesheetKey = (PathBuf, (usize, usize), usize);
+
+/// Loads assets relative to a root directory (or from embedded
+/// bytes) and caches them so every file is only decoded once.
+///
+/// With hot reloading enabled, files are polled for changes and
+/// reloaded assets are swapped into the existing handles.
+pub struct Assets {
+    root: PathBuf,
+    embedded: HashMap<PathBuf, &'static [u8]>,
+
+    images: HashMap<PathBuf, Handle<Mat<Color>>>,
+    spritesheets: HashMap<SpritesheetKey, Handle<Vec<Mat<Color>>>>,
+    textures: HashMap<PathBuf, Handle<UvMap>>,
+
+    hot_reload: Option<HotReload>,
+    on_reload_error: Box<dyn FnMut(&ResourceError)>,
+}
+
+impl Assets {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Assets {
+            root: root.into(),
+            embedded: HashMap::new(),
+            images: HashMap::new(),
+            spritesheets: HashMap::new(),
+            textures: HashMap::new(),
+            hot_reload: None,
+            on_reload_error: Box::new(|error: &ResourceError| eprintln!("{}", error)),
+        }
+    }
+
+    /// Enable or disable hot reloading, files are polled at most
+    /// every `interval`.
+    pub fn set_hot_reload(&mut self, enabled: bool, interval: Duration) {
+        if !enabled {
+            self.hot_reload = None;
+            return;
+        }
+
+        let mut modified_times = HashMap::new();
+        for path in self.images.keys() {
+            if let Some(time) = modified(&self.root.join(path)) {
+                modified_times.insert(path.to_owned(), time);
+            }
+        }
+        self.hot_reload = Some(HotReload {
+            interval,
+            last_poll: Instant::now(),
+            modified: modified_times,
+            failed: HashMap::new(),
+        });
+    }
+
+    /// Set the function called when a hot reload fails, errors are
+    /// printed to stderr by default. The old asset is kept and the
+    /// file is reloaded again at the next poll.
+    pub fn on_reload_error<F>(&mut self, on_error: F)
+    where
+        F: FnMut(&ResourceError) + 'static,
+    {
+        self.on_reload_error = Box::new(on_error);
+    }
+
+    /// Reload the changed files if hot reloading is enabled and the
+    /// poll interval has elapsed.
+    pub fn poll(&mut self) {
+        if let Some(hot_reload) = &mut self.hot_reload {
+            if hot_reload.last_poll.elapsed() >= hot_reload.interval {
+                hot_reload.last_poll = Instant::now();
+                self.reload_changed();
+            }
+        }
+    }
+
+    /// Reload every file whose modification time changed since it
+    /// was loaded, does nothing if hot reloading is disabled.
+    pub fn reload_changed(&mut self) {
+        let Some(hot_reload) = &mut self.hot_reload else {
+            return;
+        };
+
+        let mut changed = Vec::new();
+        for path in self.images.keys() {
+            if self.embedded.contains_key(path) {
+                continue;
+            }
+            if let Some(time) = modified(&self.root.join(path)) {
+                if hot_reload.modified.get(path) != Some(&time) {
+                    changed.push((path.to_owned(), time));
+                }
+            }
+        }
+
+        for (path, time) in changed {
+            let result = self.reload(&path);
+            let hot_reload = self.hot_reload.as_mut().unwrap();
+            match result {
+                Ok(()) => {
+                    hot_reload.failed.remove(&path);
+                    hot_reload.modified.insert(path, time);
+                }
+                // The file may still be being written, it is tried
+                // again silently until it changes.
+                Err(error) => {
+                    if hot_reload.failed.insert(path, time) != Some(time) {
+                        (self.on_reload_error)(&error);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reload the image at `path` and everything built from it, the
+    /// dimensions of the image must not change.
+    fn reload(&mut self, path: &Path) -> Result<(), ResourceError> {
+        let image = self.load(path)?;
+
+        let handle = &self.images[path];
+        let expected = *handle.get().dims();
+        if *image.dims() != expected {
+            return Err(ResourceError::DimensionMismatch {
+                path: self.root.join(path),
+                expected,
+                found: *image.dims(),
+            });
+        }
+
+        for ((sheet_path, sprite_dims, n_sprites), sheet) in &self.spritesheets {
+            if sheet_path == path {
+                sheet.replace(split_spritesheet(
+                    &self.root.join(path),
+                    &image,
+                    *sprite_dims,
+                    *n_sprites,
+                )?);
+            }
+        }
+        if let Some(texture) = self.textures.get(path) {
+            texture.replace(UvMap::new(image.clone()));
+        }
+        handle.replace(image);
+
+        Ok(())
+    }
+
+    /// Use `bytes` (for example from `include_bytes!`) as the content
+    /// of the file at `path` instead of reading it from the disk.
+    pub fn embed(&mut self, path: impl AsRef<Path>, bytes: &'static [u8]) {
+        self.embedded.insert(path.as_ref().to_owned(), bytes);
+    }
+
+    fn load(&self, path: &Path) -> Result<Mat<Color>, ResourceError> {
+        match self.embedded.get(path) {
+            Some(bytes) => decode_image(path, bytes),
+            None => open_image(self.root.join(path)),
+        }
+    }
+
+    /// Load the sprite at `path`.
+    pub fn sprite(&mut self, path: impl AsRef<Path>) -> Result<Handle<Mat<Color>>, ResourceError> {
+        let path = path.as_ref();
+        if let Some(handle) = self.images.get(path) {
+            return Ok(handle.clone());
+        }
+
+        let handle = Handle::new(self.load(path)?);
+        self.images.insert(path.to_owned(), handle.clone());
+        if let Some(hot_reload) = &mut self.hot_reload {
+            if let Some(time) = modified(&self.root.join(path)) {
+                hot_reload.modified.insert(path.to_owned(), time);
+            }
+        }
+        Ok(handle)
+    }
+
+    /// Load the sprites of the spritesheet at `path`, see
+    /// `resources::import_spritesheet`.
+    pub fn spritesheet(
+        &mut self,
+        path: impl AsRef<Path>,
+        sprite_dims: (usize, usize),
+        n_sprites: usize,
+    ) -> Result<Handle<Vec<Mat<Color>>>, ResourceError> {
+        let key = (path.as_ref().to_owned(), sprite_dims, n_sprites);
+        if let Some(handle) = self.spritesheets.get(&key) {
+            return Ok(handle.clone());
+        }
+
+        let image = self.sprite(&key.0)?;
+        let handle = Handle::new(split_spritesheet(
+            &self.root.join(&key.0),
+            &image.get(),
+            sprite_dims,
+            n_sprites,
+        )?);
+        self.spritesheets.insert(key, handle.clone());
+        Ok(handle)
+    }
+
+    /// Load the texture at `path` as a UvMap.
+    pub fn texture(&mut self, path: impl AsRef<Path>) -> Result<Handle<UvMap>, ResourceError> {
+        let path = path.as_ref();
+        if let Some(handle) = self.textures.get(path) {
+            return Ok(handle.clone());
+        }
+
+        let image = self.sprite(path)?;
+        let handle = Handle::new(UvMap::new(image.get().clone()));
+        self.textures.insert(path.to_owned(), handle.clone());
+        Ok(handle)
+    }
+
+    /// Forget every cached asset, handles that are still alive stay
+    /// valid.
+    pub fn clear(&mut self) {
+        self.images.clear();
+        self.spritesheets.clear();
+        self.textures.clear();
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+    /// Changing the root changes the files the paths point to, so
+    /// every cached asset is forgotten (see `Assets::clear`).
+    pub fn root_mut(&mut self) -> &mut PathBuf {
+        self.clear();
+        &mut self.root
+    }
+}
+
+impl Default for Assets {
+    fn default() -> Self {
+        Assets::new(ASSETS_PATH)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::RefCell,
+        fs::File,
+        rc::Rc,
+        time::{Duration, SystemTime},
+    };
+
+    use crate::{
+        color::Color,
+        mat::Mat,
+        resources::{mat_to_image, ResourceError},
+    };
+
+    use super::{Assets, Handle};
+
+    #[test]
+    fn cache() {
+        let mut assets = Assets::default();
+
+        let a = assets.sprite("sprites/standing.png").unwrap();
+        let b = assets.sprite("sprites/standing.png").unwrap();
+        assert!(Handle::ptr_eq(&a, &b));
+
+        let sheet = assets
+            .spritesheet("spritesheets/walking.png", (24, 24), 10)
+            .unwrap();
+        assert_eq!(sheet.get().len(), 10);
+        assert!(Handle::ptr_eq(
+            &sheet,
+            &assets
+                .spritesheet("spritesheets/walking.png", (24, 24), 10)
+                .unwrap()
+        ));
+
+        *assets.root_mut() = "does/not/exist".into();
+        assert!(assets.sprite("sprites/standing.png").is_err());
+        assert_eq!(*a.get().dims(), (24, 24));
+    }
+
+    #[test]
+    fn embedded() {
+        let mut assets = Assets::new("does/not/exist");
+        assets.embed(
+            "standing.png",
+            include_bytes!("../../assets/sprites/standing.png"),
+        );
+
+        assert_eq!(
+            *assets.sprite("standing.png").unwrap().get().dims(),
+            (24, 24)
+        );
+        assert!(assets.sprite("walking.png").is_err());
+    }
+
+    #[test]
+    fn hot_reload() {
+        let root =
+            std::env::temp_dir().join(format!("pixel_game_hot_reload_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("sprite.png");
+        let write = |color: Color, dims: (usize, usize), seconds: u64| {
+            mat_to_image(&Mat::filled_with(color, dims))
+                .save(&path)
+                .unwrap();
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+                .unwrap();
+        };
+
+        write(Color::RED, (2, 2), 1);
+
+        let mut assets = Assets::new(&root);
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_clone = Rc::clone(&errors);
+        // Set before hot reloading is enabled, it is kept.
+        assets.on_reload_error(move |error| {
+            errors_clone
+                .borrow_mut()
+                .push(matches!(error, ResourceError::DimensionMismatch { .. }))
+        });
+        assets.set_hot_reload(true, Duration::ZERO);
+
+        let sprite = assets.sprite("sprite.png").unwrap();
+        let sheet = assets.spritesheet("sprite.png", (1, 2), 2).unwrap();
+        assert_eq!(sprite.get()[(0, 0)], Color::RED);
+
+        assets.reload_changed();
+        assert_eq!(sprite.get()[(0, 0)], Color::RED);
+
+        write(Color::BLUE, (2, 2), 2);
+        assets.poll();
+        assert_eq!(sprite.get()[(0, 0)], Color::BLUE);
+        assert_eq!(sheet.get()[1][(0, 0)], Color::BLUE);
+
+        write(Color::GREEN, (3, 2), 3);
+        assets.reload_changed();
+        assets.reload_changed();
+        assert_eq!(sprite.get()[(0, 0)], Color::BLUE);
+        assert_eq!(*errors.borrow(), vec![true]);
+
+        // Fixed without changing the modification time, the failed
+        // reload is tried again.
+        write(Color::GREEN, (2, 2), 3);
+        assets.reload_changed();
+        assert_eq!(sprite.get()[(0, 0)], Color::GREEN);
+        assert_eq!(*errors.borrow(), vec![true]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
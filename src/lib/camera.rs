@@ -0,0 +1,261 @@
+use crate::{object::Object, vec2::Vec2};
+
+struct Shake {
+    intensity: f64,
+    duration: f64,
+    elapsed: f64,
+}
+
+struct Pan {
+    from: Vec2,
+    to: Vec2,
+    duration: f64,
+    elapsed: f64,
+}
+
+/// The part of the world that is rendered. The position can be
+/// anything (negative, fractional), `Camera::render_pos` is always
+/// a whole pixel position inside the world.
+pub struct Camera {
+    /// Top left corner of the camera in px.
+    pos: Vec2,
+    /// Dimensions of the rendered area in px.
+    dims: Vec2,
+    /// Dimensions of the world in px.
+    bounds: Vec2,
+
+    /// Point the camera keeps in its dead zone.
+    target: Option<Vec2>,
+    /// Dimensions of the area around the center of the camera where
+    /// the target can move without moving the camera.
+    dead_zone: Vec2,
+    /// How fast the camera catches up with the target in 1/s, 0
+    /// means instantly.
+    smoothing: f64,
+
+    shake: Option<Shake>,
+    shake_offset: Vec2,
+    pan: Option<Pan>,
+
+    /// State of the xorshift generator used for shaking.
+    seed: u64,
+}
+
+impl Camera {
+    pub fn new(pos: Vec2, dims: Vec2, bounds: Vec2) -> Self {
+        Camera {
+            pos,
+            dims,
+            bounds,
+            target: None,
+            dead_zone: Vec2::ZERO,
+            smoothing: 0.,
+            shake: None,
+            shake_offset: Vec2::ZERO,
+            pan: None,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    /// Keep the center of `object` in the dead zone, call it every
+    /// update to follow the object.
+    pub fn follow(&mut self, object: &Object) {
+        self.target = Some(*object.pos() + *object.dims() / 2.);
+    }
+
+    /// Start shaking the camera for `duration` seconds, the offset
+    /// is at most `intensity` px and fades out.
+    pub fn shake(&mut self, intensity: f64, duration: f64) {
+        self.shake = Some(Shake {
+            intensity,
+            duration,
+            elapsed: 0.,
+        });
+    }
+
+    /// Move the center of the camera to `point` in `duration`
+    /// seconds, following is suspended until the pan is done.
+    pub fn pan_to(&mut self, point: Vec2, duration: f64) {
+        self.pan = Some(Pan {
+            from: self.pos,
+            to: point - self.dims / 2.,
+            duration,
+            elapsed: 0.,
+        });
+    }
+
+    /// Return true if a pan is in progress.
+    pub fn panning(&self) -> bool {
+        self.pan.is_some()
+    }
+
+    fn random(&mut self) -> f64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// The position the camera moves to so the target is in the dead
+    /// zone.
+    fn followed_pos(&self, target: Vec2) -> Vec2 {
+        let center = self.pos + self.dims / 2.;
+        let half_zone = self.dead_zone / 2.;
+        let axis = |center: f64, target: f64, half_zone: f64| {
+            if target > center + half_zone {
+                target - half_zone
+            } else if target < center - half_zone {
+                target + half_zone
+            } else {
+                center
+            }
+        };
+        Vec2(
+            axis(center.0, target.0, half_zone.0),
+            axis(center.1, target.1, half_zone.1),
+        ) - self.dims / 2.
+    }
+
+    /// Clamp a camera position so the camera stays in the world.
+    fn clamp(&self, pos: Vec2) -> Vec2 {
+        let max = Vec2(
+            (self.bounds.0 - self.dims.0).max(0.),
+            (self.bounds.1 - self.dims.1).max(0.),
+        );
+        Vec2(pos.0.clamp(0., max.0), pos.1.clamp(0., max.1))
+    }
+
+    /// Update following, panning and shaking.
+    pub fn update(&mut self, dt: f64) {
+        if let Some(pan) = &mut self.pan {
+            pan.elapsed += dt;
+            let t = if pan.duration > 0. {
+                (pan.elapsed / pan.duration).min(1.)
+            } else {
+                1.
+            };
+            // Smoothstep so the pan eases in and out.
+            let t = t * t * (3. - 2. * t);
+            self.pos = pan.from + (pan.to - pan.from) * t;
+            if pan.elapsed >= pan.duration {
+                self.pan = None;
+            }
+        } else if let Some(target) = self.target {
+            let followed = self.followed_pos(target);
+            self.pos += if self.smoothing > 0. {
+                (followed - self.pos) * (1. - (-self.smoothing * dt).exp())
+            } else {
+                followed - self.pos
+            };
+        }
+        self.pos = self.clamp(self.pos);
+
+        self.shake_offset = Vec2::ZERO;
+        if let Some(shake) = &mut self.shake {
+            shake.elapsed += dt;
+            if shake.elapsed >= shake.duration {
+                self.shake = None;
+            } else {
+                let amplitude = shake.intensity * (1. - shake.elapsed / shake.duration);
+                self.shake_offset = Vec2(
+                    (self.random() * 2. - 1.) * amplitude,
+                    (self.random() * 2. - 1.) * amplitude,
+                );
+            }
+        }
+    }
+
+    /// The whole pixel position used for rendering, shake included.
+    pub fn render_pos(&self) -> Vec2 {
+        let pos = self.clamp(self.pos + self.shake_offset);
+        Vec2(pos.0.floor(), pos.1.floor())
+    }
+
+    pub fn pos(&self) -> &Vec2 {
+        &self.pos
+    }
+    pub fn pos_mut(&mut self) -> &mut Vec2 {
+        &mut self.pos
+    }
+    pub fn dims(&self) -> &Vec2 {
+        &self.dims
+    }
+    pub fn bounds(&self) -> &Vec2 {
+        &self.bounds
+    }
+    pub fn bounds_mut(&mut self) -> &mut Vec2 {
+        &mut self.bounds
+    }
+    pub fn target(&self) -> &Option<Vec2> {
+        &self.target
+    }
+    pub fn target_mut(&mut self) -> &mut Option<Vec2> {
+        &mut self.target
+    }
+    pub fn dead_zone(&self) -> &Vec2 {
+        &self.dead_zone
+    }
+    pub fn dead_zone_mut(&mut self) -> &mut Vec2 {
+        &mut self.dead_zone
+    }
+    pub fn smoothing(&self) -> &f64 {
+        &self.smoothing
+    }
+    pub fn smoothing_mut(&mut self) -> &mut f64 {
+        &mut self.smoothing
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{object::Object, vec2::Vec2};
+
+    use super::Camera;
+
+    #[test]
+    fn follow() {
+        let mut camera = Camera::new(Vec2::ZERO, Vec2(10., 10.), Vec2(40., 20.));
+        *camera.dead_zone_mut() = Vec2(4., 4.);
+
+        let mut object = Object::new(Vec2(5., 5.), Vec2(0., 0.), None);
+        camera.follow(&object);
+        camera.update(0.1);
+        assert_eq!(*camera.pos(), Vec2::ZERO);
+
+        object.pos_mut().0 = 20.5;
+        camera.follow(&object);
+        camera.update(0.1);
+        assert_eq!(*camera.pos(), Vec2(13.5, 0.));
+        assert_eq!(camera.render_pos(), Vec2(13., 0.));
+
+        object.pos_mut().1 = 100.;
+        camera.follow(&object);
+        camera.update(0.1);
+        assert_eq!(*camera.pos(), Vec2(13.5, 10.));
+
+        *camera.pos_mut() = Vec2(-3.7, 4.2);
+        assert_eq!(camera.render_pos(), Vec2(0., 4.));
+    }
+
+    #[test]
+    fn shake_and_pan() {
+        let mut camera = Camera::new(Vec2(10., 5.), Vec2(10., 10.), Vec2(40., 20.));
+
+        camera.shake(3., 0.5);
+        for _ in 0..4 {
+            camera.update(0.1);
+            let offset = camera.render_pos() - Vec2(10., 5.);
+            assert!(offset.0.abs() <= 3. && offset.1.abs() <= 3.);
+        }
+        camera.update(0.2);
+        assert_eq!(camera.render_pos(), Vec2(10., 5.));
+
+        camera.pan_to(Vec2(30., 10.), 1.);
+        camera.update(0.5);
+        assert!(camera.panning());
+        assert_eq!(*camera.pos(), Vec2(17.5, 5.));
+        camera.update(0.5);
+        assert!(!camera.panning());
+        assert_eq!(*camera.pos(), Vec2(25., 5.));
+    }
+}
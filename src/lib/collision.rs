@@ -0,0 +1,268 @@
+//! Swept AABB collisions of moving Objects against static ones,
+//! built on `Object::boundaries`. Boxes that only touch don't
+//! collide, so an Object can slide along a floor it rests on.
+
+use std::borrow::Borrow;
+
+use crate::{
+    object::{Boundaries, Object},
+    vec2::Vec2,
+};
+
+/// How many times `move_and_slide` changes direction in a single
+/// call at most, enough for a corner between a floor and a wall.
+const MAX_SLIDES: usize = 4;
+
+/// Where a moving box hits another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    /// The fraction of the displacement travelled before the
+    /// contact, between 0 and 1.
+    pub time: f64,
+    /// The normal of the surface that was hit, pointing towards the
+    /// moving box: `Vec2(0., -1.)` for a floor.
+    pub normal: Vec2,
+}
+
+/// Return true if the boxes overlap, touching isn't overlapping.
+fn overlapping(a: &Boundaries, b: &Boundaries) -> bool {
+    a.right > b.left && a.left < b.right && a.bottom > b.top && a.top < b.bottom
+}
+
+/// When the moving interval `a` enters and exits the static
+/// interval `b` along an axis, as fractions of `d`.
+fn axis_times(a: (f64, f64), b: (f64, f64), d: f64) -> Option<(f64, f64)> {
+    if d > 0. {
+        Some(((b.0 - a.1) / d, (b.1 - a.0) / d))
+    } else if d < 0. {
+        Some(((b.1 - a.0) / d, (b.0 - a.1) / d))
+    } else if a.1 > b.0 && a.0 < b.1 {
+        Some((f64::NEG_INFINITY, f64::INFINITY))
+    } else {
+        None
+    }
+}
+
+/// Sweep the box `moving` along `displacement` and return where it
+/// first hits `obstacle`, if it does. Boxes that already overlap
+/// are ignored, see `depenetrate`.
+pub fn sweep(moving: Boundaries, displacement: Vec2, obstacle: Boundaries) -> Option<Hit> {
+    let (entry_x, exit_x) = axis_times(
+        (moving.left, moving.right),
+        (obstacle.left, obstacle.right),
+        displacement.0,
+    )?;
+    let (entry_y, exit_y) = axis_times(
+        (moving.top, moving.bottom),
+        (obstacle.top, obstacle.bottom),
+        displacement.1,
+    )?;
+
+    let entry = entry_x.max(entry_y);
+    let exit = exit_x.min(exit_y);
+    if entry >= exit || !(0. ..=1.).contains(&entry) {
+        return None;
+    }
+
+    // Landing on a corner counts as landing on the floor.
+    let normal = if entry_x > entry_y {
+        Vec2(-displacement.0.signum(), 0.)
+    } else {
+        Vec2(0., -displacement.1.signum())
+    };
+    Some(Hit {
+        time: entry,
+        normal,
+    })
+}
+
+/// Sweep `object` along `displacement` and return the index of the
+/// first obstacle it hits and the Hit.
+pub fn sweep_all(
+    object: &Object,
+    displacement: Vec2,
+    obstacles: &[impl Borrow<Object>],
+) -> Option<(usize, Hit)> {
+    let boundaries = object.boundaries();
+    obstacles
+        .iter()
+        .enumerate()
+        .filter_map(|(i, obstacle)| {
+            sweep(boundaries, displacement, obstacle.borrow().boundaries()).map(|hit| (i, hit))
+        })
+        .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time))
+}
+
+/// Push `object` out of the obstacles it overlaps, along the axis
+/// it overlaps them the least, and return how much it moved.
+pub fn depenetrate(object: &mut Object, obstacles: &[impl Borrow<Object>]) -> Vec2 {
+    let mut push = Vec2::ZERO;
+    for obstacle in obstacles {
+        let (a, b) = (object.boundaries(), obstacle.borrow().boundaries());
+        if !overlapping(&a, &b) {
+            continue;
+        }
+        let candidates = [
+            Vec2(b.left - a.right, 0.),
+            Vec2(b.right - a.left, 0.),
+            Vec2(0., b.top - a.bottom),
+            Vec2(0., b.bottom - a.top),
+        ];
+        let smallest = candidates
+            .into_iter()
+            .min_by(|u, v| u.abs().length().total_cmp(&v.abs().length()))
+            .unwrap();
+        *object.pos_mut() += smallest;
+        push += smallest;
+    }
+    push
+}
+
+/// What `move_and_slide` did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Movement {
+    /// How much the object moved, depenetration included.
+    pub moved: Vec2,
+    /// The obstacles that were hit (their index) and where, in
+    /// order.
+    pub hits: Vec<(usize, Hit)>,
+}
+
+impl Movement {
+    /// Return true if the object hit a floor.
+    pub fn on_floor(&self) -> bool {
+        self.hits.iter().any(|(_, hit)| hit.normal.1 < 0.)
+    }
+    /// Return true if the object hit a ceiling.
+    pub fn on_ceiling(&self) -> bool {
+        self.hits.iter().any(|(_, hit)| hit.normal.1 > 0.)
+    }
+    /// Return true if the object hit a wall.
+    pub fn on_wall(&self) -> bool {
+        self.hits.iter().any(|(_, hit)| hit.normal.0 != 0.)
+    }
+
+    /// Remove the parts of `v` going into the surfaces that were
+    /// hit, so the object doesn't keep pushing against them.
+    pub fn clip_velocity(&self, mut v: Vec2) -> Vec2 {
+        for (_, hit) in &self.hits {
+            let into = v.dot_product(hit.normal);
+            if into < 0. {
+                v -= hit.normal * into;
+            }
+        }
+        v
+    }
+}
+
+/// Move `object` by `displacement` without going through the
+/// obstacles: it stops at the first surface it hits and the rest of
+/// the displacement continues along that surface.
+pub fn move_and_slide(
+    object: &mut Object,
+    displacement: Vec2,
+    obstacles: &[impl Borrow<Object>],
+) -> Movement {
+    let mut movement = Movement {
+        moved: depenetrate(object, obstacles),
+        hits: Vec::new(),
+    };
+
+    let mut remaining = displacement;
+    for _ in 0..MAX_SLIDES {
+        if remaining == Vec2::ZERO {
+            break;
+        }
+        let Some((i, hit)) = sweep_all(object, remaining, obstacles) else {
+            *object.pos_mut() += remaining;
+            movement.moved += remaining;
+            break;
+        };
+
+        let start = *object.pos();
+        *object.pos_mut() += remaining * hit.time;
+        // Put the object exactly against the surface, rounding
+        // errors would make it overlap or float.
+        let (a, b) = (object.boundaries(), obstacles[i].borrow().boundaries());
+        match (hit.normal.0 as i8, hit.normal.1 as i8) {
+            (-1, _) => object.pos_mut().0 += b.left - a.right,
+            (1, _) => object.pos_mut().0 += b.right - a.left,
+            (_, -1) => object.pos_mut().1 += b.top - a.bottom,
+            _ => object.pos_mut().1 += b.bottom - a.top,
+        }
+        movement.moved += *object.pos() - start;
+        movement.hits.push((i, hit));
+
+        // Only the part along the surface is left.
+        remaining *= 1. - hit.time;
+        remaining -= hit.normal * remaining.dot_product(hit.normal);
+    }
+    movement
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{object::Object, vec2::Vec2};
+
+    use super::{depenetrate, move_and_slide, sweep, Hit};
+
+    #[test]
+    fn sweep_hits() {
+        // Boundaries are one pixel bigger than dims.
+        let moving = Object::new(Vec2(0., 0.), Vec2(1., 1.), None).boundaries();
+        let wall = Object::new(Vec2(10., -5.), Vec2(1., 20.), None).boundaries();
+
+        assert_eq!(
+            sweep(moving, Vec2(16., 0.), wall),
+            Some(Hit {
+                time: 0.5,
+                normal: Vec2(-1., 0.)
+            })
+        );
+        assert_eq!(sweep(moving, Vec2(4., 0.), wall), None);
+        assert_eq!(sweep(moving, Vec2(-16., 0.), wall), None);
+        // Passes above the wall.
+        assert_eq!(sweep(moving, Vec2(16., -16.), wall), None);
+
+        let floor = Object::new(Vec2(-5., 2.), Vec2(20., 1.), None).boundaries();
+        // Touching the floor doesn't stop from walking on it.
+        assert_eq!(sweep(moving, Vec2(3., 0.), floor), None);
+        assert_eq!(
+            sweep(moving, Vec2(3., 1.), floor),
+            Some(Hit {
+                time: 0.,
+                normal: Vec2(0., -1.)
+            })
+        );
+    }
+
+    #[test]
+    fn no_tunneling() {
+        let platform = Object::new(Vec2(0., 40.), Vec2(80., 2.), None);
+        let mut object = Object::new(Vec2(10., 0.), Vec2(4., 4.), None);
+
+        // Way more than the thickness of the platform in one step.
+        let movement = move_and_slide(&mut object, Vec2(30., 500.), &[&platform]);
+        assert!(movement.on_floor() && !movement.on_wall());
+        assert_eq!(object.boundaries().bottom, platform.boundaries().top);
+        assert!((object.pos().0 - 40.).abs() < 1e-9);
+        assert_eq!(movement.clip_velocity(Vec2(3., 60.)), Vec2(3., 0.));
+
+        // Sliding into a wall while resting on the floor.
+        let wall = Object::new(Vec2(50., 20.), Vec2(2., 20.), None);
+        let movement = move_and_slide(&mut object, Vec2(20., 1.), &[&platform, &wall]);
+        assert!(movement.on_floor() && movement.on_wall());
+        assert_eq!(object.boundaries().right, wall.boundaries().left);
+        assert_eq!(object.boundaries().bottom, platform.boundaries().top);
+    }
+
+    #[test]
+    fn depenetration() {
+        let platform = Object::new(Vec2(0., 40.), Vec2(40., 4.), None);
+        let mut object = Object::new(Vec2(10., 38.), Vec2(4., 4.), None);
+
+        assert_eq!(depenetrate(&mut object, &[&platform]), Vec2(0., -3.));
+        assert_eq!(object.boundaries().bottom, platform.boundaries().top);
+        assert_eq!(depenetrate(&mut object, &[&platform]), Vec2::ZERO);
+    }
+}
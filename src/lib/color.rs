@@ -1,3 +1,26 @@
+/// How a color is combined with the color it is drawn over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Only fully opaque colors are drawn, the others are skipped.
+    #[default]
+    Opaque,
+    /// Regular alpha compositing (source over).
+    Alpha,
+    /// Add the colors, for glows and lights.
+    Additive,
+    /// Multiply the colors, for shadows and tinting.
+    Multiply,
+    /// Inverse of multiply, always lightens.
+    Screen,
+}
+
+fn to_unit(value: u8) -> f64 {
+    value as f64 / 255.
+}
+fn from_unit(value: f64) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
     pub r: u8,
@@ -30,4 +53,95 @@ impl Color {
     pub const fn to_bytes(&self) -> [u8; 4] {
         [self.r, self.g, self.b, self.a]
     }
+
+    /// The color with its alpha multiplied by `opacity` (between 0
+    /// and 1).
+    pub fn with_opacity(&self, opacity: f64) -> Self {
+        Color {
+            a: from_unit(to_unit(self.a) * opacity),
+            ..*self
+        }
+    }
+
+    /// Draw the color over `dst` combining the channels with `f`
+    /// (W3C separable blending followed by source over
+    /// compositing).
+    fn composite<F>(&self, dst: Color, f: F) -> Self
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let (sa, da) = (to_unit(self.a), to_unit(dst.a));
+        let a = sa + da * (1. - sa);
+        if a == 0. {
+            return Color::TRANSPARENT;
+        }
+
+        let channel = |s: u8, d: u8| {
+            let (s, d) = (to_unit(s), to_unit(d));
+            let mixed = (1. - da) * s + da * f(s, d);
+            from_unit((sa * mixed + da * (1. - sa) * d) / a)
+        };
+        Color {
+            r: channel(self.r, dst.r),
+            g: channel(self.g, dst.g),
+            b: channel(self.b, dst.b),
+            a: from_unit(a),
+        }
+    }
+
+    /// Alpha composite the color over `dst`.
+    pub fn over(&self, dst: Color) -> Self {
+        self.composite(dst, |s, _| s)
+    }
+    /// Add the color to `dst`.
+    pub fn additive(&self, dst: Color) -> Self {
+        self.composite(dst, |s, d| s + d)
+    }
+    /// Multiply the color with `dst`.
+    pub fn multiply(&self, dst: Color) -> Self {
+        self.composite(dst, |s, d| s * d)
+    }
+    /// Screen the color with `dst`.
+    pub fn screen(&self, dst: Color) -> Self {
+        self.composite(dst, |s, d| s + d - s * d)
+    }
+
+    /// Draw the color over `dst` with the given blend mode.
+    pub fn blend(&self, dst: Color, mode: BlendMode) -> Self {
+        match mode {
+            BlendMode::Opaque if self.a == 255 => *self,
+            BlendMode::Opaque => dst,
+            BlendMode::Alpha => self.over(dst),
+            BlendMode::Additive => self.additive(dst),
+            BlendMode::Multiply => self.multiply(dst),
+            BlendMode::Screen => self.screen(dst),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BlendMode, Color};
+
+    #[test]
+    fn blend() {
+        let gray = Color::new(128, 128, 128, 255);
+        let half_red = Color::new(255, 0, 0, 128);
+
+        assert_eq!(half_red.blend(gray, BlendMode::Opaque), gray);
+        assert_eq!(Color::RED.blend(gray, BlendMode::Opaque), Color::RED);
+
+        assert_eq!(half_red.over(gray), Color::new(192, 64, 64, 255));
+        assert_eq!(Color::RED.over(gray), Color::RED);
+        assert_eq!(Color::TRANSPARENT.over(gray), gray);
+        assert_eq!(half_red.over(Color::TRANSPARENT), half_red);
+
+        assert_eq!(Color::RED.additive(gray), Color::new(255, 128, 128, 255));
+        assert_eq!(Color::RED.multiply(gray), Color::new(128, 0, 0, 255));
+        assert_eq!(Color::RED.screen(gray), Color::new(255, 128, 128, 255));
+        assert_eq!(Color::WHITE.multiply(gray), gray);
+
+        assert_eq!(Color::RED.with_opacity(0.5), Color::new(255, 0, 0, 128));
+        assert_eq!(Color::RED.with_opacity(0.5).over(gray), half_red.over(gray));
+    }
 }
@@ -0,0 +1,429 @@
+//! A platformer character moved by its input rather than by
+//! forces, only gravity comes from its Physics.
+
+use crate::{collision, object::Object, physics::Physics, vec2::Vec2};
+
+/// The tuning of a CharacterController, speeds are in px/s and
+/// durations in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControllerParams {
+    pub walk_speed: f64,
+    /// The fraction of `walk_speed` reached in the air.
+    pub air_control: f64,
+    pub jump_speed: f64,
+    /// What the upward velocity is multiplied by when jump is
+    /// released early, lower means shorter small jumps.
+    pub jump_cut: f64,
+    pub max_fall_speed: f64,
+    /// How long after walking off a ledge jumping still works.
+    pub coyote_time: f64,
+    /// How long before landing pressing jump still works.
+    pub jump_buffer: f64,
+    /// How far a grounded character is pulled down every update to
+    /// stay on the slopes going down, it is also how far below a
+    /// slope its feet can go when walking up before falling through.
+    pub slope_snap: f64,
+}
+
+impl Default for ControllerParams {
+    fn default() -> Self {
+        ControllerParams {
+            walk_speed: 15.,
+            air_control: 0.8,
+            jump_speed: 80.,
+            jump_cut: 0.5,
+            max_fall_speed: 200.,
+            coyote_time: 0.1,
+            jump_buffer: 0.1,
+            slope_snap: 2.,
+        }
+    }
+}
+
+/// What the player wants the character to do this update.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ControllerInput {
+    /// -1 to go left, 1 to go right.
+    pub direction: f64,
+    pub jump_pressed: bool,
+    pub jump_held: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A surface going from `from` to `to` (left to right) that can
+/// be walked on from above, like a one-way platform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slope {
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+impl Slope {
+    pub fn new(from: Vec2, to: Vec2) -> Self {
+        Slope { from, to }
+    }
+
+    /// The height of the surface at `x`, None past its ends.
+    pub fn y_at(&self, x: f64) -> Option<f64> {
+        if x < self.from.0 || x > self.to.0 || self.from.0 == self.to.0 {
+            return None;
+        }
+        let t = (x - self.from.0) / (self.to.0 - self.from.0);
+        Some(self.from.1 + (self.to.1 - self.from.1) * t)
+    }
+}
+
+/// What a CharacterController collides with.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Terrain<'a> {
+    pub solids: &'a [&'a Object],
+    /// Only block the character from above, it can jump through
+    /// them from below.
+    pub one_way: &'a [&'a Object],
+    pub slopes: &'a [Slope],
+}
+
+/// Moves an Object like a platformer character: walking, coyote
+/// time, buffered and variable height jumps, one-way platforms and
+/// slopes.
+#[derive(Debug, Clone)]
+pub struct CharacterController {
+    object: Object,
+    physics: Physics,
+    params: ControllerParams,
+
+    grounded: bool,
+    landed: bool,
+    jumped: bool,
+    wall: Option<Side>,
+    /// Time left to jump after leaving the ground.
+    coyote: f64,
+    /// Time left for a jump press to be used.
+    buffer: f64,
+    /// Rising from a jump that can still be cut.
+    rising: bool,
+}
+
+impl CharacterController {
+    /// Create a controller moving `object`, the weight of `physics`
+    /// becomes its total force and its position is replaced by the
+    /// one of `object`.
+    pub fn new(object: Object, mut physics: Physics, params: ControllerParams) -> Self {
+        *physics.pos_mut() = *object.pos();
+        physics.set_tf_to_w();
+        CharacterController {
+            object,
+            physics,
+            params,
+            grounded: false,
+            landed: false,
+            jumped: false,
+            wall: None,
+            coyote: 0.,
+            buffer: 0.,
+            rising: false,
+        }
+    }
+
+    /// The bottom of the object's hitbox and its horizontal center.
+    fn feet(&self) -> Vec2 {
+        let b = self.object.boundaries();
+        Vec2((b.left + b.right) / 2., b.bottom)
+    }
+
+    pub fn update(&mut self, dt: f64, input: &ControllerInput, terrain: &Terrain) {
+        let params = self.params;
+        let was_grounded = self.grounded;
+
+        self.coyote = if was_grounded {
+            params.coyote_time
+        } else {
+            self.coyote - dt
+        };
+        self.buffer = if input.jump_pressed {
+            params.jump_buffer
+        } else {
+            self.buffer - dt
+        };
+
+        let speed = if was_grounded {
+            params.walk_speed
+        } else {
+            params.walk_speed * params.air_control
+        };
+        self.physics.v_mut().0 = input.direction.clamp(-1., 1.) * speed;
+
+        self.jumped = self.buffer > 0. && self.coyote > 0.;
+        if self.jumped {
+            self.physics.v_mut().1 = -params.jump_speed;
+            self.buffer = 0.;
+            self.coyote = 0.;
+            self.rising = true;
+        }
+        if self.rising && (self.physics.v().1 >= 0. || !input.jump_held) {
+            if self.physics.v().1 < 0. {
+                self.physics.v_mut().1 *= params.jump_cut;
+            }
+            self.rising = false;
+        }
+
+        let start = *self.object.pos();
+        let start_feet = self.feet();
+        self.physics.update(dt);
+        let mut displacement = *self.physics.pos() - start;
+        if was_grounded && !self.jumped {
+            // Stays on the slopes going down instead of flying off.
+            displacement.1 = displacement.1.max(params.slope_snap);
+        }
+
+        // One-way platforms only count when they are under the
+        // feet.
+        let obstacles: Vec<&Object> = terrain
+            .solids
+            .iter()
+            .copied()
+            .chain(
+                terrain
+                    .one_way
+                    .iter()
+                    .copied()
+                    .filter(|o| start_feet.1 <= o.boundaries().top),
+            )
+            .collect();
+        let movement = collision::move_and_slide(&mut self.object, displacement, &obstacles);
+        let mut v = movement.clip_velocity(*self.physics.v());
+        self.grounded = movement.on_floor();
+
+        if v.1 >= 0. {
+            if let Some(surface) = self.slope_under(terrain, start_feet) {
+                self.object.pos_mut().1 += surface - self.feet().1;
+                self.grounded = true;
+                v.1 = 0.;
+            }
+        }
+        v.1 = v.1.min(params.max_fall_speed);
+
+        *self.physics.pos_mut() = *self.object.pos();
+        *self.physics.v_mut() = v;
+
+        self.landed = self.grounded && !was_grounded;
+        if self.grounded {
+            self.rising = false;
+        }
+        self.wall = [(Side::Left, -1.), (Side::Right, 1.)]
+            .into_iter()
+            .find(|(_, dx)| {
+                collision::sweep_all(&self.object, Vec2(*dx, 0.), terrain.solids)
+                    .is_some_and(|(_, hit)| hit.time == 0.)
+            })
+            .map(|(side, _)| side);
+    }
+
+    /// The height of the slope the feet should be put on, if any:
+    /// the feet went through it from above.
+    fn slope_under(&self, terrain: &Terrain, start_feet: Vec2) -> Option<f64> {
+        let feet = self.feet();
+        terrain
+            .slopes
+            .iter()
+            .filter_map(|slope| {
+                let surface = slope.y_at(feet.0)?;
+                let start_surface = slope.y_at(start_feet.0).unwrap_or(surface);
+                let above_before = start_feet.1 <= start_surface + self.params.slope_snap;
+                (above_before && feet.1 >= surface).then_some(surface)
+            })
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    pub fn object(&self) -> &Object {
+        &self.object
+    }
+    pub fn physics(&self) -> &Physics {
+        &self.physics
+    }
+    pub fn physics_mut(&mut self) -> &mut Physics {
+        &mut self.physics
+    }
+    pub fn params(&self) -> &ControllerParams {
+        &self.params
+    }
+    pub fn params_mut(&mut self) -> &mut ControllerParams {
+        &mut self.params
+    }
+
+    /// Whether the character stands on a floor, a one-way platform
+    /// or a slope.
+    pub fn grounded(&self) -> bool {
+        self.grounded
+    }
+    /// Return true if the character landed during the last update.
+    pub fn landed(&self) -> bool {
+        self.landed
+    }
+    /// Return true if the character jumped during the last update.
+    pub fn jumped(&self) -> bool {
+        self.jumped
+    }
+    /// The side of the character touching a solid, if any.
+    pub fn wall(&self) -> Option<Side> {
+        self.wall
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{object::Object, physics::Physics, vec2::Vec2};
+
+    use super::{CharacterController, ControllerInput, ControllerParams, Side, Slope, Terrain};
+
+    const DT: f64 = 1. / 60.;
+
+    fn character(pos: Vec2) -> CharacterController {
+        CharacterController::new(
+            Object::new(pos, Vec2(4., 8.), None),
+            Physics::new(Vec2::ZERO, Vec2::ZERO, 1., 300.),
+            ControllerParams::default(),
+        )
+    }
+
+    fn run(
+        character: &mut CharacterController,
+        ticks: usize,
+        input: ControllerInput,
+        terrain: &Terrain,
+    ) {
+        for _ in 0..ticks {
+            character.update(DT, &input, terrain);
+        }
+    }
+
+    const WALK: ControllerInput = ControllerInput {
+        direction: 1.,
+        jump_pressed: false,
+        jump_held: false,
+    };
+    const JUMP: ControllerInput = ControllerInput {
+        direction: 0.,
+        jump_pressed: true,
+        jump_held: true,
+    };
+    const HOLD: ControllerInput = ControllerInput {
+        direction: 0.,
+        jump_pressed: false,
+        jump_held: true,
+    };
+    const IDLE: ControllerInput = ControllerInput {
+        direction: 0.,
+        jump_pressed: false,
+        jump_held: false,
+    };
+
+    #[test]
+    fn jumps() {
+        let floor = Object::new(Vec2(0., 40.), Vec2(100., 4.), None);
+        let terrain = Terrain {
+            solids: &[&floor],
+            ..Default::default()
+        };
+        let mut c = character(Vec2(10., -20.));
+
+        // Pressing jump a bit before landing still jumps.
+        while c.object().boundaries().bottom < 37. {
+            c.update(DT, &IDLE, &terrain);
+        }
+        assert!(!c.grounded());
+        c.update(DT, &JUMP, &terrain);
+        assert!((0..5).any(|_| {
+            c.update(DT, &HOLD, &terrain);
+            c.jumped()
+        }));
+
+        // Holding jump goes higher than tapping it.
+        let apex = |c: &mut CharacterController, input| {
+            run(c, 120, IDLE, &terrain);
+            assert!(c.grounded());
+            let mut highest = c.object().pos().1;
+            c.update(DT, &JUMP, &terrain);
+            for _ in 0..60 {
+                c.update(DT, &input, &terrain);
+                highest = highest.min(c.object().pos().1);
+            }
+            assert!(c.landed() || c.grounded());
+            highest
+        };
+        let held = apex(&mut c, HOLD);
+        let tapped = apex(&mut c, IDLE);
+        assert!(held < tapped - 4., "{} {}", held, tapped);
+    }
+
+    #[test]
+    fn coyote_time() {
+        let ledge = Object::new(Vec2(0., 40.), Vec2(20., 4.), None);
+        let terrain = Terrain {
+            solids: &[&ledge],
+            ..Default::default()
+        };
+        let mut c = character(Vec2(10., 31.));
+        run(&mut c, 2, IDLE, &terrain);
+        assert!(c.grounded() && c.wall().is_none());
+
+        // Walks off the ledge, jumping still works for a moment.
+        while c.grounded() {
+            c.update(DT, &WALK, &terrain);
+        }
+        run(&mut c, 3, WALK, &terrain);
+        c.update(DT, &JUMP, &terrain);
+        assert!(c.jumped());
+
+        let mut late = character(Vec2(10., 31.));
+        run(&mut late, 2, IDLE, &terrain);
+        while late.grounded() {
+            late.update(DT, &WALK, &terrain);
+        }
+        run(&mut late, 10, WALK, &terrain);
+        late.update(DT, &JUMP, &terrain);
+        assert!(!late.jumped());
+    }
+
+    #[test]
+    fn terrain() {
+        let floor = Object::new(Vec2(0., 40.), Vec2(200., 4.), None);
+        let wall = Object::new(Vec2(150., 0.), Vec2(4., 40.), None);
+        let bridge = Object::new(Vec2(0., 30.), Vec2(40., 1.), None);
+        let slope = Slope::new(Vec2(60., 40.), Vec2(100., 20.));
+        let terrain = Terrain {
+            solids: &[&floor, &wall],
+            one_way: &[&bridge],
+            slopes: &[slope],
+        };
+
+        // Jumps through the bridge from below and lands on it.
+        let mut c = character(Vec2(10., 31.));
+        c.params_mut().jump_speed = 100.;
+        run(&mut c, 2, IDLE, &terrain);
+        c.update(DT, &JUMP, &terrain);
+        run(&mut c, 60, HOLD, &terrain);
+        assert!(c.grounded());
+        assert_eq!(c.object().boundaries().bottom, bridge.boundaries().top);
+
+        // Walks off the bridge then up the slope.
+        run(&mut c, 60 * 4, WALK, &terrain);
+        assert!(c.grounded());
+        let feet = c.object().boundaries();
+        let x = (feet.left + feet.right) / 2.;
+        assert!(x > 60. && x < 100.);
+        assert!((feet.bottom - slope.y_at(x).unwrap()).abs() < 1e-9);
+
+        // Past the top of the slope it falls back to the floor and
+        // walks into the wall.
+        run(&mut c, 60 * 6, WALK, &terrain);
+        assert!(c.grounded());
+        assert_eq!(c.wall(), Some(Side::Right));
+        assert_eq!(c.object().boundaries().right, wall.boundaries().left);
+    }
+}
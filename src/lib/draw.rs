@@ -0,0 +1,434 @@
+use crate::{color::Color, mat::Mat, vec2::Vec2};
+
+/// Draws primitives on a Mat, every pixel outside of the clip
+/// area is skipped. Positions are in px, they are floored to the
+/// pixel they fall in so they can be negative or fractional.
+pub struct Painter<'a> {
+    mat: &'a mut Mat<Color>,
+    /// Top left corner of the clip area (included).
+    min: (i64, i64),
+    /// Bottom right corner of the clip area (excluded).
+    max: (i64, i64),
+}
+
+fn pixel(pos: Vec2) -> (i64, i64) {
+    (pos.0.floor() as i64, pos.1.floor() as i64)
+}
+
+// Sides of the clip area a point is outside of, for
+// `clip_line`.
+const LEFT: u8 = 1;
+const RIGHT: u8 = 2;
+const TOP: u8 = 4;
+const BOTTOM: u8 = 8;
+
+fn outcode(p: Vec2, min: Vec2, max: Vec2) -> u8 {
+    let mut code = 0;
+    if p.0 < min.0 {
+        code |= LEFT;
+    } else if p.0 > max.0 {
+        code |= RIGHT;
+    }
+    if p.1 < min.1 {
+        code |= TOP;
+    } else if p.1 > max.1 {
+        code |= BOTTOM;
+    }
+    code
+}
+
+/// Clip the segment from `a` to `b` to the area from `min` to
+/// `max` (Cohen–Sutherland), None if it is entirely outside.
+fn clip_line(mut a: Vec2, mut b: Vec2, min: Vec2, max: Vec2) -> Option<(Vec2, Vec2)> {
+    // Each end is moved to at most two sides, more only happens
+    // with rounding errors and the Painter skips those pixels.
+    for _ in 0..4 {
+        let (code_a, code_b) = (outcode(a, min, max), outcode(b, min, max));
+        if code_a | code_b == 0 {
+            break;
+        }
+        if code_a & code_b != 0 {
+            return None;
+        }
+
+        let code = if code_a != 0 { code_a } else { code_b };
+        // Halved so the difference of huge coordinates doesn't
+        // overflow, and interpolated so the result doesn't either.
+        let at = |from: f64, to: f64, value: f64| (value / 2. - from / 2.) / (to / 2. - from / 2.);
+        let lerp = |from: f64, to: f64, t: f64| from * (1. - t) + to * t;
+        let p = if code & (LEFT | RIGHT) != 0 {
+            let x = if code & LEFT != 0 { min.0 } else { max.0 };
+            Vec2(x, lerp(a.1, b.1, at(a.0, b.0, x)))
+        } else {
+            let y = if code & TOP != 0 { min.1 } else { max.1 };
+            Vec2(lerp(a.0, b.0, at(a.1, b.1, y)), y)
+        };
+        if code == code_a {
+            a = p;
+        } else {
+            b = p;
+        }
+    }
+    Some((a, b))
+}
+
+impl<'a> Painter<'a> {
+    /// Create a Painter that can draw on the whole Mat.
+    pub fn new(mat: &'a mut Mat<Color>) -> Self {
+        let dims = *mat.dims();
+        Painter {
+            mat,
+            min: (0, 0),
+            max: (dims.0 as i64, dims.1 as i64),
+        }
+    }
+
+    /// Create a Painter that can only draw on the area starting at
+    /// `pos` with dimensions `dims` (and inside the Mat).
+    pub fn clipped(mat: &'a mut Mat<Color>, pos: Vec2, dims: Vec2) -> Self {
+        let mut painter = Painter::new(mat);
+        let (min, max) = (pixel(pos), pixel(pos + dims));
+        painter.min = (painter.min.0.max(min.0), painter.min.1.max(min.1));
+        painter.max = (painter.max.0.min(max.0), painter.max.1.min(max.1));
+        painter
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: Color) {
+        if self.min.0 <= x && x < self.max.0 && self.min.1 <= y && y < self.max.1 {
+            self.mat[(x as usize, y as usize)] = color;
+        }
+    }
+
+    /// Fill the pixels from `x0` to `x1` (included) of row `y`.
+    fn span(&mut self, x0: i64, x1: i64, y: i64, color: Color) {
+        if y < self.min.1 || self.max.1 <= y {
+            return;
+        }
+        for x in x0.max(self.min.0)..=x1.min(self.max.0 - 1) {
+            self.mat[(x as usize, y as usize)] = color;
+        }
+    }
+
+    pub fn pixel(&mut self, pos: Vec2, color: Color) {
+        let (x, y) = pixel(pos);
+        self.set(x, y, color);
+    }
+
+    /// Draw a line from `from` to `to` (both included) with
+    /// Bresenham's algorithm.
+    pub fn line(&mut self, from: Vec2, to: Vec2, color: Color) {
+        // The line is clipped first so only the pixels of the clip
+        // area are walked, however far the ends are.
+        let floor = |pos: Vec2| Vec2(pos.0.floor(), pos.1.floor());
+        let Some((from, to)) = clip_line(
+            floor(from),
+            floor(to),
+            Vec2(self.min.0 as f64, self.min.1 as f64),
+            Vec2((self.max.0 - 1) as f64, (self.max.1 - 1) as f64),
+        ) else {
+            return;
+        };
+        let round = |pos: Vec2| (pos.0.round() as i64, pos.1.round() as i64);
+        let ((mut x, mut y), (x1, y1)) = (round(from), round(to));
+
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut err = dx + dy;
+        loop {
+            self.set(x, y, color);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Draw the outline of the rectangle starting at `pos` with
+    /// dimensions `dims`.
+    pub fn rect(&mut self, pos: Vec2, dims: Vec2, color: Color) {
+        let (x0, y0) = pixel(pos);
+        let (w, h) = pixel(dims);
+        if w <= 0 || h <= 0 {
+            return;
+        }
+        let (x1, y1) = (x0 + w - 1, y0 + h - 1);
+        self.span(x0, x1, y0, color);
+        self.span(x0, x1, y1, color);
+        for y in (y0 + 1).max(self.min.1)..y1.min(self.max.1) {
+            self.set(x0, y, color);
+            self.set(x1, y, color);
+        }
+    }
+
+    /// Fill the rectangle starting at `pos` with dimensions `dims`.
+    pub fn fill_rect(&mut self, pos: Vec2, dims: Vec2, color: Color) {
+        let (x0, y0) = pixel(pos);
+        let (w, h) = pixel(dims);
+        for y in y0.max(self.min.1)..(y0 + h).min(self.max.1) {
+            self.span(x0, x0 + w - 1, y, color);
+        }
+    }
+
+    /// Call `plot` with the points of the first octant of a circle
+    /// centered on the origin (midpoint algorithm).
+    fn circle_octant(radius: i64, mut plot: impl FnMut(i64, i64)) {
+        let (mut x, mut y) = (radius, 0);
+        let mut err = 1 - radius;
+        while x >= y {
+            plot(x, y);
+            y += 1;
+            if err < 0 {
+                err += 2 * y + 1;
+            } else {
+                x -= 1;
+                err += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    /// Draw the outline of a circle with the midpoint algorithm,
+    /// `radius` is rounded to the nearest pixel.
+    pub fn circle(&mut self, center: Vec2, radius: f64, color: Color) {
+        let (cx, cy) = pixel(center);
+        let mut points = Vec::new();
+        Painter::circle_octant(radius.round() as i64, |x, y| points.push((x, y)));
+        for (x, y) in points {
+            for (px, py) in [(x, y), (y, x)] {
+                self.set(cx + px, cy + py, color);
+                self.set(cx - px, cy + py, color);
+                self.set(cx + px, cy - py, color);
+                self.set(cx - px, cy - py, color);
+            }
+        }
+    }
+
+    /// Fill a circle, see `Painter::circle`.
+    pub fn fill_circle(&mut self, center: Vec2, radius: f64, color: Color) {
+        let (cx, cy) = pixel(center);
+        let mut points = Vec::new();
+        Painter::circle_octant(radius.round() as i64, |x, y| points.push((x, y)));
+        for (x, y) in points {
+            for (px, py) in [(x, y), (y, x)] {
+                self.span(cx - px, cx + px, cy + py, color);
+                self.span(cx - px, cx + px, cy - py, color);
+            }
+        }
+    }
+
+    /// Call `plot` with the points of the first quadrant of an
+    /// ellipse centered on the origin (midpoint algorithm).
+    fn ellipse_quadrant(radii: (i64, i64), mut plot: impl FnMut(i64, i64)) {
+        let (rx, ry) = radii;
+        if ry == 0 {
+            // The algorithm only plots the center of flat ellipses.
+            for x in 0..=rx {
+                plot(x, 0);
+            }
+            return;
+        }
+        let (rx2, ry2) = ((rx * rx) as f64, (ry * ry) as f64);
+        let (mut x, mut y) = (0, ry);
+        let (mut dx, mut dy) = (0., 2. * rx2 * y as f64);
+
+        // Where the slope is less than 1, x always moves.
+        let mut d = ry2 - rx2 * ry as f64 + rx2 / 4.;
+        while dx < dy {
+            plot(x, y);
+            x += 1;
+            dx += 2. * ry2;
+            if d < 0. {
+                d += dx + ry2;
+            } else {
+                y -= 1;
+                dy -= 2. * rx2;
+                d += dx - dy + ry2;
+            }
+        }
+
+        // Where it is greater, y always moves.
+        let mut d = ry2 * (x as f64 + 0.5).powi(2) + rx2 * ((y - 1) as f64).powi(2) - rx2 * ry2;
+        while y >= 0 {
+            plot(x, y);
+            y -= 1;
+            dy -= 2. * rx2;
+            if d > 0. {
+                d += rx2 - dy;
+            } else {
+                x += 1;
+                dx += 2. * ry2;
+                d += dx - dy + rx2;
+            }
+        }
+    }
+
+    /// Draw the outline of an ellipse with the midpoint algorithm,
+    /// `radii` are rounded to the nearest pixel.
+    pub fn ellipse(&mut self, center: Vec2, radii: Vec2, color: Color) {
+        let (cx, cy) = pixel(center);
+        let mut points = Vec::new();
+        Painter::ellipse_quadrant((radii.0.round() as i64, radii.1.round() as i64), |x, y| {
+            points.push((x, y))
+        });
+        for (x, y) in points {
+            self.set(cx + x, cy + y, color);
+            self.set(cx - x, cy + y, color);
+            self.set(cx + x, cy - y, color);
+            self.set(cx - x, cy - y, color);
+        }
+    }
+
+    /// Fill an ellipse, see `Painter::ellipse`.
+    pub fn fill_ellipse(&mut self, center: Vec2, radii: Vec2, color: Color) {
+        let (cx, cy) = pixel(center);
+        let mut points = Vec::new();
+        Painter::ellipse_quadrant((radii.0.round() as i64, radii.1.round() as i64), |x, y| {
+            points.push((x, y))
+        });
+        for (x, y) in points {
+            self.span(cx - x, cx + x, cy + y, color);
+            self.span(cx - x, cx + x, cy - y, color);
+        }
+    }
+
+    /// Draw the outline of a polygon, the last point is joined to
+    /// the first one.
+    pub fn polygon(&mut self, points: &[Vec2], color: Color) {
+        for (i, &point) in points.iter().enumerate() {
+            self.line(point, points[(i + 1) % points.len()], color);
+        }
+    }
+
+    /// Fill a polygon with the scanline algorithm (even-odd rule),
+    /// a pixel is filled if its center is inside the polygon.
+    pub fn fill_polygon(&mut self, points: &[Vec2], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+        let (min_y, max_y) = points.iter().fold((f64::MAX, f64::MIN), |(min, max), p| {
+            (min.min(p.1), max.max(p.1))
+        });
+
+        let mut crossings = Vec::new();
+        let y0 = (min_y.floor() as i64).max(self.min.1);
+        let y1 = (max_y.ceil() as i64).min(self.max.1);
+        for y in y0..y1 {
+            let scan_y = y as f64 + 0.5;
+
+            crossings.clear();
+            for (i, a) in points.iter().enumerate() {
+                let b = points[(i + 1) % points.len()];
+                if (a.1 <= scan_y) != (b.1 <= scan_y) {
+                    crossings.push(a.0 + (scan_y - a.1) / (b.1 - a.1) * (b.0 - a.0));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+
+            for pair in crossings.chunks_exact(2) {
+                let x0 = (pair[0] - 0.5).ceil() as i64;
+                let x1 = (pair[1] - 0.5).ceil() as i64 - 1;
+                self.span(x0, x1, y, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        color::Color,
+        mat::{Mat, MatSlice},
+        vec2::Vec2,
+    };
+
+    use super::Painter;
+
+    fn count(mat: &Mat<Color>) -> usize {
+        mat.vec().iter().filter(|c| **c == Color::WHITE).count()
+    }
+
+    #[test]
+    fn shapes() {
+        let mut mat = Mat::filled_with(Color::BLACK, (8, 8));
+
+        Painter::new(&mut mat).line(Vec2(0., 0.), Vec2(7., 3.), Color::WHITE);
+        assert_eq!(count(&mat), 8);
+        assert_eq!(mat[(7, 3)], Color::WHITE);
+
+        mat.fill(Color::BLACK);
+        Painter::new(&mut mat).rect(Vec2(1., 1.), Vec2(4., 3.), Color::WHITE);
+        assert_eq!(count(&mat), 10);
+        Painter::new(&mut mat).fill_rect(Vec2(1., 1.), Vec2(4., 3.), Color::WHITE);
+        assert_eq!(count(&mat), 12);
+
+        mat.fill(Color::BLACK);
+        Painter::new(&mut mat).circle(Vec2(3., 3.), 3., Color::WHITE);
+        assert_eq!(count(&mat), 16);
+        Painter::new(&mut mat).fill_circle(Vec2(3., 3.), 3., Color::WHITE);
+        assert_eq!(count(&mat), 37);
+        mat.fill(Color::BLACK);
+        Painter::new(&mut mat).ellipse(Vec2(3., 3.), Vec2(3., 3.), Color::WHITE);
+        assert_eq!(count(&mat), 16);
+
+        mat.fill(Color::BLACK);
+        Painter::new(&mut mat).fill_polygon(
+            &[Vec2(0., 0.), Vec2(4., 0.), Vec2(4., 4.), Vec2(0., 4.)],
+            Color::WHITE,
+        );
+        assert_eq!(count(&mat), 16);
+        mat.fill(Color::BLACK);
+        Painter::new(&mut mat)
+            .fill_polygon(&[Vec2(0., 0.), Vec2(8., 0.), Vec2(0., 8.)], Color::WHITE);
+        assert_eq!(count(&mat), 28);
+    }
+
+    #[test]
+    fn clipping() {
+        let mut mat = Mat::filled_with(Color::BLACK, (8, 8));
+        let mut painter = Painter::clipped(&mut mat, Vec2(2., 2.), Vec2(4., 4.));
+
+        painter.fill_rect(Vec2(-10., -10.), Vec2(100., 100.), Color::WHITE);
+        painter.line(Vec2(-100., 0.), Vec2(100., 7.), Color::WHITE);
+        painter.fill_circle(Vec2(-1.5, 9.), 5., Color::WHITE);
+        painter.fill_polygon(
+            &[Vec2(-5., -5.), Vec2(20., 3.), Vec2(3., 20.)],
+            Color::WHITE,
+        );
+        assert_eq!(count(&mat), 16);
+        for x in 2..6 {
+            for y in 2..6 {
+                assert_eq!(mat[(x, y)], Color::WHITE);
+            }
+        }
+    }
+
+    #[test]
+    fn far_lines() {
+        let mut mat = Mat::filled_with(Color::BLACK, (8, 8));
+        let mut painter = Painter::new(&mut mat);
+
+        painter.line(Vec2(-1e300, 3.), Vec2(f64::MAX, 3.), Color::WHITE);
+        painter.line(Vec2(5., f64::MIN), Vec2(5., 1e300), Color::WHITE);
+        assert_eq!(count(&mat), 15);
+        for i in 0..8 {
+            assert_eq!(mat[(i, 3)], Color::WHITE);
+            assert_eq!(mat[(5, i)], Color::WHITE);
+        }
+
+        mat.fill(Color::BLACK);
+        let mut painter = Painter::new(&mut mat);
+        painter.line(Vec2(-1e12, -1e12), Vec2(1e12, 1e12), Color::WHITE);
+        painter.line(Vec2(-1e12, 0.), Vec2(-1e12, 7.), Color::WHITE);
+        assert_eq!(count(&mat), 8);
+        for i in 0..8 {
+            assert_eq!(mat[(i, i)], Color::WHITE);
+        }
+    }
+}
@@ -1,7 +1,10 @@
+use std::path::Path;
+
 use crate::{
+    assets::Handle,
     color::Color,
     mat::Mat,
-    resources::{import_sprites, import_spritesheet},
+    resources::{import_sprites, import_spritesheet, open_image, ResourceError, ASSETS_PATH},
 };
 
 pub enum Drawable {
@@ -52,93 +55,89 @@ impl Iterator for Drawable {
     fn next(&mut self) -> Option<Self::Item> {
         match self {
             Drawable::Animation(d) => {
-                d.frame = (d.frame + 1) % d.states[d.state].len();
-                Some(d.states[d.state][d.frame].to_owned())
+                let frames = d.states[d.state].get();
+                d.frame = (d.frame + 1) % frames.len();
+                Some(frames[d.frame].to_owned())
             }
             Drawable::Frames(d) => {
-                d.state = (d.state + 1) % d.states.len();
-                Some(d.states[d.state].to_owned())
+                let states = d.states.get();
+                d.state = (d.state + 1) % states.len();
+                Some(states[d.state].to_owned())
             }
-            Drawable::UniqueFrame(d) => Some(d.state.to_owned()),
+            Drawable::UniqueFrame(d) => Some(d.state.get().to_owned()),
         }
     }
 }
 
 pub struct UniqueFrame {
     dims: (usize, usize),
-    state: Mat<Color>,
+    state: Handle<Mat<Color>>,
 }
 
 impl UniqueFrame {
-    pub fn from_file(path: &str, dims: (usize, usize)) -> Self {
-        let image: image::ImageBuffer<image::Rgba<u8>, Vec<u8>> =
-            image::open(path).unwrap().to_rgba8();
+    pub fn from_file(path: &str) -> Result<Self, ResourceError> {
+        Ok(UniqueFrame::from_handle(Handle::new(open_image(path)?)))
+    }
 
-        UniqueFrame {
-            dims,
-            state: Mat::from_vec(
-                image
-                    .as_raw()
-                    .chunks(4)
-                    .map(|v| Color {
-                        r: v[0],
-                        g: v[1],
-                        b: v[2],
-                        a: v[3],
-                    })
-                    .collect::<Vec<_>>(),
-                dims,
-            ),
-        }
+    /// Create a UniqueFrame from a sprite loaded with `Assets`, it
+    /// follows the sprite when it is hot reloaded.
+    pub fn from_handle(state: Handle<Mat<Color>>) -> Self {
+        let dims = *state.get().dims();
+        UniqueFrame { dims, state }
     }
 
     pub fn from_color(color: Color, dims: (usize, usize)) -> Self {
-        let dims = dims;
         UniqueFrame {
             dims,
-            state: Mat::filled_with(color, dims),
+            state: Handle::new(Mat::filled_with(color, dims)),
         }
     }
 }
 
-impl Into<Drawable> for UniqueFrame {
-    fn into(self) -> Drawable {
-        Drawable::UniqueFrame(self)
+impl From<UniqueFrame> for Drawable {
+    fn from(value: UniqueFrame) -> Self {
+        Drawable::UniqueFrame(value)
     }
 }
 
 pub struct Frames {
     dims: (usize, usize),
     state: usize,
-    states: Vec<Mat<Color>>,
+    states: Handle<Vec<Mat<Color>>>,
 }
 
 impl Frames {
     pub fn from_spritesheet(
         path: &str,
         sprite_dims: (usize, usize),
-        spritesheet_dims: (usize, usize),
         n_sprites: usize,
-    ) -> Self {
-        Frames {
-            state: 0,
-            states: import_spritesheet(path, sprite_dims, spritesheet_dims, n_sprites),
-            dims: sprite_dims,
-        }
+    ) -> Result<Self, ResourceError> {
+        Ok(Frames::from_handle(Handle::new(import_spritesheet(
+            path,
+            sprite_dims,
+            n_sprites,
+        )?)))
+    }
+
+    pub fn from_files(paths: &[&str]) -> Result<Self, ResourceError> {
+        Ok(Frames::from_handle(Handle::new(import_sprites(paths)?)))
     }
 
-    pub fn from_files(paths: &[&str], dims: (usize, usize)) -> Self {
+    /// Create Frames from a spritesheet loaded with `Assets`, they
+    /// follow the spritesheet when it is hot reloaded.
+    pub fn from_handle(states: Handle<Vec<Mat<Color>>>) -> Self {
+        let dims = states.get().first().map_or((0, 0), |s| *s.dims());
         Frames {
             state: 0,
             dims,
-            states: import_sprites(paths, dims),
+            states,
         }
     }
 }
 
-impl Into<Drawable> for Frames {
-    fn into(self) -> Drawable {
-        Drawable::Frames(self)
+impl From<Frames> for Drawable {
+    fn from(value: Frames) -> Self {
+        Drawable::Frames(value)
     }
 }
 
@@ -146,26 +145,58 @@ pub struct Animation {
     dims: (usize, usize),
     state: usize,
     frame: usize,
-    states: Vec<Vec<Mat<Color>>>,
+    states: Vec<Handle<Vec<Mat<Color>>>>,
 }
 
 impl Animation {
-    pub fn from_files(paths: &[&[&str]], dims: (usize, usize)) -> Self {
+    /// Import the frames of each state, they must all have the
+    /// same dimensions.
+    pub fn from_files(paths: &[&[&str]]) -> Result<Self, ResourceError> {
+        let mut dims = None;
         let mut states = Vec::new();
         for frames_paths in paths {
-            states.push(import_sprites(frames_paths, dims));
+            let frames = import_sprites(frames_paths)?;
+            for (frame, path) in frames.iter().zip(frames_paths.iter()) {
+                match dims {
+                    None => dims = Some(*frame.dims()),
+                    Some(dims) if dims != *frame.dims() => {
+                        return Err(ResourceError::DimensionMismatch {
+                            path: Path::new(ASSETS_PATH).join(path),
+                            expected: dims,
+                            found: *frame.dims(),
+                        })
+                    }
+                    _ => (),
+                }
+            }
+            states.push(Handle::new(frames));
         }
-        Animation {
+        Ok(Animation {
             state: 0,
             frame: 0,
             states,
+            dims: dims.unwrap_or((0, 0)),
+        })
+    }
+
+    /// Create an Animation from spritesheets loaded with `Assets`
+    /// (one per state), it follows them when they are hot reloaded.
+    pub fn from_handles(states: Vec<Handle<Vec<Mat<Color>>>>) -> Self {
+        let dims = states
+            .first()
+            .and_then(|frames| frames.get().first().map(|frame| *frame.dims()))
+            .unwrap_or((0, 0));
+        Animation {
+            state: 0,
+            frame: 0,
             dims,
+            states,
         }
     }
 }
 
-impl Into<Drawable> for Animation {
-    fn into(self) -> Drawable {
-        Drawable::Animation(self)
+impl From<Animation> for Drawable {
+    fn from(value: Animation) -> Self {
+        Drawable::Animation(value)
     }
 }
@@ -0,0 +1,397 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    color::Color,
+    mat::{Mat, MatSlice},
+    resources::ResourceError,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// How text is rendered, see `Font::render`.
+#[derive(Clone, Copy, Debug)]
+pub struct TextOptions {
+    /// Multiplied with the color of the glyphs.
+    pub color: Color,
+    /// Lines longer than this are wrapped between words, or between
+    /// characters for the words that are too long.
+    pub max_width: Option<usize>,
+    pub align: Align,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions {
+            color: Color::WHITE,
+            max_width: None,
+            align: Align::Left,
+        }
+    }
+}
+
+struct Glyph {
+    image: Mat<Color>,
+    advance: i64,
+}
+
+/// A bitmap font made of a glyph atlas and a character map.
+///
+/// The character map is a text file with one directive per line
+/// (lines starting with `#` are comments):
+/// - `cell <width> <height>`: dimensions of a glyph in the atlas,
+///   must come before the rows
+/// - `row <chars>`: the glyphs of the next row of the atlas
+/// - `line_height <px>`, `spacing <px>` (between two glyphs) and
+///   `space <px>` (advance of the space character)
+/// - `advance <char> <px>`: override the advance of a glyph, by
+///   default it is the width of its visible part
+/// - `kern <char><char> <px>`: added to the spacing of a pair
+pub struct Font {
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), i64>,
+    cell_dims: (usize, usize),
+    line_height: usize,
+    spacing: i64,
+    space: i64,
+}
+
+impl Font {
+    /// Create a Font from its atlas and the content of its character
+    /// map, `path` is only used in errors.
+    pub fn new(
+        path: impl AsRef<Path>,
+        atlas: &Mat<Color>,
+        description: &str,
+    ) -> Result<Self, ResourceError> {
+        let error = |line: usize, message: String| ResourceError::Parse {
+            path: path.as_ref().to_owned(),
+            line: line + 1,
+            message,
+        };
+        let number = |line: usize, value: &str| {
+            value
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| error(line, format!("expected a number, found {:?}", value)))
+        };
+
+        let mut cell_dims = None;
+        let mut line_height = None;
+        let mut spacing = 1;
+        let mut space = None;
+        let mut rows = 0;
+        let mut glyphs = HashMap::new();
+        let mut advances = Vec::new();
+        let mut kerning = HashMap::new();
+
+        for (i, line) in description.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (directive, value) = line.split_once(' ').unwrap_or((line, ""));
+            match directive {
+                "cell" => {
+                    let (w, h) = value
+                        .trim()
+                        .split_once(' ')
+                        .ok_or_else(|| error(i, "expected a width and a height".to_string()))?;
+                    let dims = (number(i, w)?, number(i, h)?);
+                    if dims.0 <= 0 || dims.1 <= 0 {
+                        return Err(error(i, "cells can't be empty".to_string()));
+                    }
+                    cell_dims = Some((dims.0 as usize, dims.1 as usize));
+                }
+                "row" => {
+                    let cell_dims = cell_dims
+                        .ok_or_else(|| error(i, "rows must come after the cell".to_string()))?;
+                    let chars = value.chars().collect::<Vec<_>>();
+                    if (rows + 1) * cell_dims.1 > atlas.dims().1
+                        || chars.len() * cell_dims.0 > atlas.dims().0
+                    {
+                        return Err(error(i, "row is outside of the atlas".to_string()));
+                    }
+                    for (column, c) in chars.into_iter().enumerate() {
+                        let image = atlas
+                            .slice(
+                                (column * cell_dims.0, rows * cell_dims.1),
+                                cell_dims,
+                                (false, false),
+                            )
+                            .to_mat();
+                        let advance = (0..cell_dims.0)
+                            .rev()
+                            .find(|&x| (0..cell_dims.1).any(|y| image[(x, y)].a > 0))
+                            .map_or(0, |x| x as i64 + 1);
+                        glyphs.insert(c, Glyph { image, advance });
+                    }
+                    rows += 1;
+                }
+                "line_height" => line_height = Some(number(i, value)?.max(0) as usize),
+                "spacing" => spacing = number(i, value)?,
+                "space" => space = Some(number(i, value)?),
+                "advance" => {
+                    let mut chars = value.chars();
+                    let c = chars
+                        .next()
+                        .ok_or_else(|| error(i, "expected a character".to_string()))?;
+                    advances.push((i, c, number(i, chars.as_str())?));
+                }
+                "kern" => {
+                    let mut chars = value.chars();
+                    let (a, b) = chars
+                        .next()
+                        .zip(chars.next())
+                        .ok_or_else(|| error(i, "expected two characters".to_string()))?;
+                    kerning.insert((a, b), number(i, chars.as_str())?);
+                }
+                _ => return Err(error(i, format!("unknown directive {:?}", directive))),
+            }
+        }
+
+        for (i, c, advance) in advances {
+            glyphs
+                .get_mut(&c)
+                .ok_or_else(|| error(i, format!("{:?} isn't in any row", c)))?
+                .advance = advance;
+        }
+
+        let cell_dims = cell_dims.ok_or_else(|| error(0, "missing cell".to_string()))?;
+        Ok(Font {
+            glyphs,
+            kerning,
+            cell_dims,
+            line_height: line_height.unwrap_or(cell_dims.1),
+            spacing,
+            space: space.unwrap_or(cell_dims.0 as i64),
+        })
+    }
+
+    /// The glyph drawn for `c`, unknown characters are drawn as `?`.
+    fn glyph(&self, c: char) -> Option<&Glyph> {
+        match self.glyphs.get(&c) {
+            None if c != ' ' => self.glyphs.get(&'?'),
+            glyph => glyph,
+        }
+    }
+
+    fn advance(&self, c: char) -> i64 {
+        self.glyph(c).map_or(self.space, |glyph| glyph.advance)
+    }
+
+    /// The space between two consecutive characters.
+    fn gap(&self, a: char, b: char) -> i64 {
+        self.spacing + self.kerning.get(&(a, b)).unwrap_or(&0)
+    }
+
+    /// The width of a single line of text in px.
+    pub fn width(&self, line: &str) -> usize {
+        let mut width = 0;
+        let mut prev = None;
+        for c in line.chars() {
+            if let Some(prev) = prev {
+                width += self.gap(prev, c);
+            }
+            width += self.advance(c);
+            prev = Some(c);
+        }
+        width.max(0) as usize
+    }
+
+    /// Split `text` into lines, at line breaks and between words
+    /// when a line would be wider than `max_width`. A word wider
+    /// than `max_width` is broken between its characters.
+    pub fn wrap(&self, text: &str, max_width: Option<usize>) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let Some(max_width) = max_width else {
+                lines.push(paragraph.to_string());
+                continue;
+            };
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                let candidate = if line.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{} {}", line, word)
+                };
+                if line.is_empty() || self.width(&candidate) <= max_width {
+                    line = candidate;
+                } else {
+                    lines.push(line);
+                    line = word.to_string();
+                }
+                // A single character that doesn't fit stays alone.
+                while line.chars().nth(1).is_some() && self.width(&line) > max_width {
+                    let end = self.fitting(&line, max_width);
+                    lines.push(line[..end].to_string());
+                    line = line[end..].to_string();
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// The length in bytes of the longest start of `line` that
+    /// isn't wider than `max_width`, at least one character.
+    fn fitting(&self, line: &str, max_width: usize) -> usize {
+        let mut ends = line.char_indices().map(|(i, c)| i + c.len_utf8());
+        let first = ends.next().unwrap_or(0);
+        ends.take_while(|&end| self.width(&line[..end]) <= max_width)
+            .last()
+            .unwrap_or(first)
+    }
+
+    /// The dimensions of the Mat returned by `Font::render`.
+    pub fn measure(&self, text: &str, options: &TextOptions) -> (usize, usize) {
+        let lines = self.wrap(text, options.max_width);
+        self.block_dims(&lines, options)
+    }
+
+    fn block_dims(&self, lines: &[String], options: &TextOptions) -> (usize, usize) {
+        // Wider than `max_width` if a single character is.
+        let widest = lines.iter().map(|line| self.width(line)).max().unwrap_or(0);
+        let width = options
+            .max_width
+            .map_or(widest, |max_width| max_width.max(widest));
+        let height = (lines.len().max(1) - 1) * self.line_height + self.cell_dims.1;
+        (width, height)
+    }
+
+    /// Render `text` on a transparent Mat, the color of every glyph
+    /// is multiplied with `options.color`.
+    pub fn render(&self, text: &str, options: &TextOptions) -> Mat<Color> {
+        let lines = self.wrap(text, options.max_width);
+        let dims = self.block_dims(&lines, options);
+        let mut mat = Mat::filled_with(Color::TRANSPARENT, dims);
+
+        let tint = |c: u8, t: u8| (c as u16 * t as u16 / 255) as u8;
+
+        for (i, line) in lines.iter().enumerate() {
+            let free = dims.0 as i64 - self.width(line) as i64;
+            let mut pen = match options.align {
+                Align::Left => 0,
+                Align::Center => free / 2,
+                Align::Right => free,
+            };
+            let top = i * self.line_height;
+
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if let Some(glyph) = self.glyph(c) {
+                    for (x, y) in glyph.image.enumerate_r() {
+                        let pixel = glyph.image[(x, y)];
+                        let index = (pen + x as i64, top + y);
+                        if pixel.a > 0 && index.0 >= 0 && mat.has((index.0 as usize, index.1)) {
+                            mat[(index.0 as usize, index.1)] = Color::new(
+                                tint(pixel.r, options.color.r),
+                                tint(pixel.g, options.color.g),
+                                tint(pixel.b, options.color.b),
+                                tint(pixel.a, options.color.a),
+                            );
+                        }
+                    }
+                }
+                pen += self.advance(c);
+                if let Some(&next) = chars.peek() {
+                    pen += self.gap(c, next);
+                }
+            }
+        }
+
+        mat
+    }
+
+    pub fn line_height(&self) -> usize {
+        self.line_height
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{color::Color, mat::Mat, resources::ResourceError};
+
+    use super::{Align, Font, TextOptions};
+
+    fn font() -> Font {
+        // Three glyphs: a full 2x2 block, a 1px wide bar and a dot.
+        let mut atlas = Mat::filled_with(Color::TRANSPARENT, (9, 3));
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1), (3, 0), (3, 1), (6, 1)] {
+            atlas[(x, y)] = Color::WHITE;
+        }
+        Font::new(
+            "test.txt",
+            &atlas,
+            "# test font\ncell 3 3\nline_height 4\nrow |!.\nspace 2\nkern |! -1\nadvance . 2\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn layout() {
+        let font = font();
+        assert_eq!(font.width("|"), 2);
+        assert_eq!(font.width("||"), 5);
+        assert_eq!(font.width("|!"), 3);
+        assert_eq!(font.width("| ."), 8);
+        assert_eq!(font.width("?"), 2);
+
+        assert_eq!(font.wrap("|| | !!!\n|", Some(12)), ["|| |", "!!!", "|"]);
+        // Words wider than the max width are broken.
+        assert_eq!(font.wrap("|||| !", Some(5)), ["||", "||", "!"]);
+        assert_eq!(font.wrap("||", Some(1)), ["|", "|"]);
+        assert_eq!(
+            font.measure("|| | !!!\n|", &TextOptions::default()),
+            (20, 7)
+        );
+    }
+
+    #[test]
+    fn render() {
+        let font = font();
+        let options = TextOptions {
+            color: Color::new(255, 0, 0, 255),
+            max_width: Some(6),
+            align: Align::Right,
+        };
+        let mat = font.render("|!\n|", &options);
+        assert_eq!(*mat.dims(), (6, 7));
+        assert_eq!(mat[(3, 0)], Color::new(255, 0, 0, 255));
+        assert_eq!(mat[(5, 1)], Color::new(255, 0, 0, 255));
+        assert_eq!(mat[(2, 0)], Color::TRANSPARENT);
+        assert_eq!(mat[(4, 5)], Color::new(255, 0, 0, 255));
+
+        // Nothing is cut off.
+        let mat = font.render("||||", &options);
+        assert_eq!(*mat.dims(), (6, 7));
+        assert_eq!(mat[(5, 5)], Color::new(255, 0, 0, 255));
+        let options = TextOptions {
+            max_width: Some(1),
+            ..options
+        };
+        assert_eq!(*font.render("|", &options).dims(), (2, 3));
+    }
+
+    #[test]
+    fn errors() {
+        let atlas = Mat::filled_with(Color::WHITE, (4, 4));
+        assert!(matches!(
+            Font::new("f.txt", &atlas, "row ab"),
+            Err(ResourceError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            Font::new("f.txt", &atlas, "cell 2 2\nrow ab\nrow cd\nrow ef"),
+            Err(ResourceError::Parse { line: 4, .. })
+        ));
+        assert!(matches!(
+            Font::new("f.txt", &atlas, "cell 2 2\nspacing x"),
+            Err(ResourceError::Parse { line: 2, .. })
+        ));
+    }
+}
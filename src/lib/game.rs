@@ -1,30 +1,112 @@
+mod capture;
+mod dirty;
 mod grid;
+mod headless;
+mod layer;
+mod recording;
+mod timestep;
+
+pub use {
+    dirty::FrameStats,
+    headless::Headless,
+    layer::{Layer, WORLD_LAYER},
+    recording::{InputEvent, RecordedFrame, Recording},
+    timestep::{Accumulator, Ticks, Timestep},
+};
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use pixels::{Pixels, SurfaceTexture};
 use winit::{
     dpi::LogicalSize,
+    event::WindowEvent,
     event_loop::{ControlFlow, EventLoop},
     window::WindowBuilder,
 };
 use winit_input_helper::WinitInputHelper;
 
-use crate::{color::Color, game::grid::Grid, mat::MatSlice, vec2::Vec2};
+use crate::{
+    assets::Assets,
+    camera::Camera,
+    color::{BlendMode, Color},
+    draw::Painter,
+    font::{Font, TextOptions},
+    input::InputMap,
+    mat::{Mat, MatSlice},
+    resources::{save_image, ResourceError},
+    vec2::Vec2,
+};
+
+use capture::Capture;
+
+/// What moves every pixel of a layer on screen when it changes.
+type LayerState = (i32, bool, f64, (usize, usize));
+
+/// Create a Mat from the RGBA bytes of a frame.
+fn frame_from_bytes(pixels: &[u8], dims: (usize, usize)) -> Mat<Color> {
+    Mat::from_vec(
+        pixels
+            .chunks_exact(4)
+            .map(|c| Color::from_bytes([c[0], c[1], c[2], c[3]]))
+            .collect::<Vec<_>>(),
+        dims,
+    )
+}
 
 pub struct Game {
     dims: Vec2,
-    render_pos: Vec2,
     render_dims: Vec2,
     title: String,
 
-    grid: Grid,
+    camera: Camera,
+
+    layers: Vec<(String, Layer)>,
+    /// The index of the layer that is drawn on.
+    active_layer: usize,
+    /// The layers as they were last presented, see `Game::present`.
+    presented: Vec<LayerState>,
+    stats: FrameStats,
+    /// Buffers reused by `Game::present`.
+    rows: Vec<bool>,
+    order: Vec<(usize, (usize, usize))>,
 
     input: WinitInputHelper,
+    /// The window events not seen by an update yet, see
+    /// `Game::step_input`.
+    input_events: Vec<WindowEvent<'static>>,
+    /// The render area pixel under the cursor.
+    mouse: Option<(usize, usize)>,
+    input_map: InputMap,
+    timestep: Timestep,
+    recording: Option<Recording>,
+    capture: Option<Capture>,
+    /// Where the recording is saved when the window is closed.
+    recording_path: Option<PathBuf>,
+
+    assets: Assets,
 }
 
 impl Game {
-    pub fn run<U>(mut self, mut update: U)
+    /// Run the game, `update` is called with the Game and the dt
+    /// (in seconds) of the update.
+    pub fn run<U>(self, update: U)
     where
-        U: FnMut(&mut Game) + 'static,
+        U: FnMut(&mut Game, f64) + 'static,
+    {
+        self.run_with_render(update, |_, _| {})
+    }
+
+    /// Run the game, `update` is called with the Game and the dt
+    /// (in seconds) of the update, then `render` is called once per
+    /// frame with the interpolation factor between the previous and
+    /// the next tick (always 1 with a variable timestep).
+    pub fn run_with_render<U, R>(mut self, mut update: U, mut render: R)
+    where
+        U: FnMut(&mut Game, f64) + 'static,
+        R: FnMut(&mut Game, f64) + 'static,
     {
         let Vec2(width, height) = self.render_dims;
 
@@ -50,97 +132,491 @@ impl Game {
             Pixels::new(width as u32, height as u32, surface_texture).unwrap()
         };
 
+        let mut accumulator = Accumulator::new(self.timestep);
+        let mut last_frame = Instant::now();
+        // The input of the window, the updates see `Game::input`.
+        let mut window_input = WinitInputHelper::new();
+
         event_loop.run(move |event, _, control_flow| {
+            // Only a scale factor change can't outlive the callback,
+            // the window is resized right after it anyway.
+            let Some(event) = event.to_static() else {
+                return;
+            };
+            if let winit::event::Event::WindowEvent { event, .. } = &event {
+                if let Some(recording) = &mut self.recording {
+                    recording.record_event(event);
+                }
+                self.input_events.push(event.clone());
+            }
+
             if let winit::event::Event::RedrawRequested(_) = event {
-                self.draw_to_window(pixels.frame_mut());
+                self.present(pixels.frame_mut());
                 pixels.render().unwrap()
             }
 
-            if self.input.update(&event) {
-                if self.input.close_requested() {
-                    *control_flow = ControlFlow::Exit
+            if window_input.update(&event) {
+                if window_input.close_requested() {
+                    *control_flow = ControlFlow::Exit;
+                    self.save_recording();
+                }
+                if let Some(size) = window_input.window_resized() {
+                    pixels.resize_surface(size.width, size.height).unwrap();
                 }
+                // Maps through the scaling and letterboxing of pixels.
+                self.mouse = window_input
+                    .mouse()
+                    .and_then(|pos| pixels.window_pos_to_pixel(pos).ok());
+
+                self.assets.poll();
 
-                update(&mut self);
+                let now = Instant::now();
+                let ticks = accumulator.advance((now - last_frame).as_secs_f64());
+                last_frame = now;
+
+                for _ in 0..ticks.steps {
+                    self.step_input();
+                    self.camera.update(ticks.dt);
+                    update(&mut self, ticks.dt);
+                }
+                if let Some(recording) = &mut self.recording {
+                    recording.end_frame(ticks.steps, self.mouse);
+                }
+                render(&mut self, ticks.alpha);
 
                 window.request_redraw();
             }
         });
     }
 
+    /// Draw `image` at `pos`, only its fully opaque pixels are drawn.
+    /// Return the pixels that were changed.
     pub fn image_at(&mut self, pos: Vec2, image: &impl MatSlice<Color>) -> Vec<Vec2> {
-        let image_dims = image.slice_dims();
-
-        let mut changed_pixels = Vec::new();
-        for x in 0..image_dims.0 {
-            for y in 0..image_dims.1 {
-                let index: Vec2 = pos + Vec2::from_usize(x, y);
-                let max_render_pos = self.render_pos + self.render_dims;
-
-                if 0. <= index.0
-                    && 0. <= index.1
-                    && index.0 <= max_render_pos.0
-                    && index.1 <= max_render_pos.1
-                {
-                    let pixel = image[(x, y)];
-                    if pixel.a == 255 && self.grid.mat().has(index.to_usize()) {
-                        self.grid.mat_mut()[index.to_usize()] = pixel;
-                        changed_pixels.push(index);
-                    }
-                }
-            }
-        }
-        changed_pixels
+        self.image_at_blend(pos, image, BlendMode::Opaque, 1.)
+    }
+
+    /// Draw `image` at `pos` with the given blend mode, the alpha of
+    /// every pixel is multiplied by `opacity` (between 0 and 1).
+    /// Return the pixels that were changed.
+    pub fn image_at_blend(
+        &mut self,
+        pos: Vec2,
+        image: &impl MatSlice<Color>,
+        mode: BlendMode,
+        opacity: f64,
+    ) -> Vec<Vec2> {
+        let max_render_pos = self.layer_render_pos() + self.render_dims;
+        self.layers[self.active_layer]
+            .1
+            .grid_mut()
+            .draw(pos, image, mode, opacity, max_render_pos)
+    }
+
+    /// Draw `text` with its top left corner at `pos`, see
+    /// `Font::render`. Return the pixels that were changed.
+    pub fn text(&mut self, pos: Vec2, font: &Font, text: &str, options: &TextOptions) -> Vec<Vec2> {
+        let image = font.render(text, options);
+        self.image_at_blend(pos, &image, BlendMode::Alpha, 1.)
+    }
+
+    /// Reset the given pixels (for example the ones returned by
+    /// `Game::image_at`) to the background color.
+    pub fn clear_pixels(&mut self, pixels: &[Vec2]) {
+        self.layers[self.active_layer]
+            .1
+            .grid_mut()
+            .clear_pixels(pixels);
+    }
+
+    /// Reset the pixels of the area starting at `pos` with
+    /// dimensions `dims` to the background color.
+    pub fn clear_region(&mut self, pos: Vec2, dims: Vec2) {
+        self.layers[self.active_layer]
+            .1
+            .grid_mut()
+            .clear_region(pos, dims);
     }
 
+    /// A Painter drawing on the active layer, clipped to the render
+    /// area.
+    pub fn painter(&mut self) -> Painter<'_> {
+        let pos = self.layer_render_pos();
+        self.layers[self.active_layer]
+            .1
+            .grid_mut()
+            .painter(pos, self.render_dims)
+    }
+
+    /// Fill the whole active layer with `color`.
     pub fn clear(&mut self, color: Color) {
-        let dims = self.grid.dims().to_usize();
-        for x in 0..dims.0 {
-            for y in 0..dims.1 {
-                self.grid.mat_mut()[(x, y)] = color;
-            }
+        self.layers[self.active_layer].1.grid_mut().fill(color);
+    }
+
+    /// Reset what was drawn on the active layer since the last call
+    /// to the background color. Unlike `Game::clear`, only the
+    /// regions that were drawn on are cleared and uploaded again.
+    pub fn clear_dirty(&mut self) {
+        self.layers[self.active_layer].1.grid_mut().clear_dirty();
+    }
+
+    /// What the last presented frame cost.
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Add a layer on top of the ones with the same z-index, or
+    /// replace the layer with the same name.
+    pub fn add_layer(&mut self, name: impl Into<String>, layer: Layer) {
+        let name = name.into();
+        match self.layers.iter().position(|(n, _)| *n == name) {
+            Some(i) => self.layers[i].1 = layer,
+            None => self.layers.push((name, layer)),
         }
+        // The whole render area is uploaded again, the new layer may
+        // cover less than the one it replaces.
+        self.presented.clear();
+    }
+
+    /// Draw on the layer named `name` from now on (`image_at`,
+    /// `painter`, `clear`, ...), the world layer is the default.
+    pub fn select_layer(&mut self, name: &str) {
+        self.active_layer = self
+            .layers
+            .iter()
+            .position(|(n, _)| n == name)
+            .unwrap_or_else(|| panic!("Game has no layer named {:?}.", name));
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, layer)| layer)
+    }
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers
+            .iter_mut()
+            .find(|(n, _)| n == name)
+            .map(|(_, layer)| layer)
+    }
+
+    /// The position of the render area in the active layer.
+    fn layer_render_pos(&self) -> Vec2 {
+        self.layers[self.active_layer]
+            .1
+            .render_pos(self.camera.render_pos(), self.render_dims)
     }
 
     pub fn dims(&self) -> &Vec2 {
         &self.dims
     }
 
+    /// The position of the camera, see `Camera::pos`.
     pub fn pos(&self) -> &Vec2 {
-        &self.render_pos
+        self.camera.pos()
     }
     pub fn pos_mut(&mut self) -> &mut Vec2 {
-        &mut self.render_pos
+        self.camera.pos_mut()
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
     }
 
     pub fn input(&self) -> &WinitInputHelper {
         &self.input
     }
+    /// Show the window events since the last tick to `Game::input`,
+    /// called before every tick. A press or release is only seen by
+    /// the first tick after it, even when frames run no tick.
+    fn step_input(&mut self) {
+        self.input.step_with_window_events(&self.input_events);
+        self.input_events.clear();
+    }
 
-    fn draw_to_window(&self, pixels: &mut [u8]) {
-        for (c, pix) in self
-            .grid
-            .mat()
-            .slice(
-                self.render_pos.to_usize(),
-                self.render_dims.to_usize(),
-                (false, false),
-            )
-            .to_vec()
-            .iter()
-            .zip(pixels.chunks_exact_mut(4))
-        {
-            pix.copy_from_slice(&c.to_bytes());
+    /// The position of the cursor in the render area, None when it
+    /// is outside of it.
+    pub fn mouse_render_pos(&self) -> Option<Vec2> {
+        self.mouse.map(|(x, y)| Vec2::from_usize(x, y))
+    }
+    /// The position of the cursor in the world, None when it is
+    /// outside of the render area.
+    pub fn mouse_world_pos(&self) -> Option<Vec2> {
+        self.mouse_render_pos()
+            .map(|pos| pos + self.camera.render_pos())
+    }
+    /// The position of the cursor in the layer named `name`, which
+    /// differs from `Game::mouse_world_pos` with parallax.
+    pub fn mouse_layer_pos(&self, name: &str) -> Option<Vec2> {
+        let layer = self.layer(name)?;
+        self.mouse_render_pos()
+            .map(|pos| pos + layer.render_pos(self.camera.render_pos(), self.render_dims))
+    }
+
+    pub fn input_map(&self) -> &InputMap {
+        &self.input_map
+    }
+    pub fn input_map_mut(&mut self) -> &mut InputMap {
+        &mut self.input_map
+    }
+
+    /// See `InputMap::pressed`.
+    pub fn action_pressed(&self, action: &str) -> bool {
+        self.input_map.pressed(&self.input, action)
+    }
+    /// See `InputMap::held`.
+    pub fn action_held(&self, action: &str) -> bool {
+        self.input_map.held(&self.input, action)
+    }
+    /// See `InputMap::released`.
+    pub fn action_released(&self, action: &str) -> bool {
+        self.input_map.released(&self.input, action)
+    }
+
+    /// Start recording the input, see `Recording`. An ongoing
+    /// recording is discarded.
+    ///
+    /// Panics if the Game has a variable timestep, the updates
+    /// couldn't be replayed.
+    pub fn start_recording(&mut self) {
+        let dt = self
+            .timestep
+            .dt()
+            .expect("Recording the input needs a fixed timestep.");
+        self.recording = Some(Recording::new(dt));
+    }
+    /// Stop recording the input and return what was recorded.
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref()
+    }
+
+    /// Save the recording to the path given to
+    /// `GameBuilder::record`, if any.
+    fn save_recording(&self) {
+        if let (Some(recording), Some(path)) = (&self.recording, &self.recording_path) {
+            if let Err(error) = recording.save(path) {
+                eprintln!("{}", error);
+            }
+        }
+    }
+
+    pub fn timestep(&self) -> &Timestep {
+        &self.timestep
+    }
+
+    pub fn assets(&self) -> &Assets {
+        &self.assets
+    }
+    pub fn assets_mut(&mut self) -> &mut Assets {
+        &mut self.assets
+    }
+
+    /// Create a new Mat holding the pixels of the render area,
+    /// this is what gets drawn to the window.
+    pub fn frame(&self) -> Mat<Color> {
+        let (width, height) = self.render_dims.to_usize();
+        let mut order = Vec::new();
+        self.layer_order(&mut order);
+        let mut pixels = vec![0; width * height * 4];
+        self.compose(&order, 0..height, &mut pixels);
+        frame_from_bytes(&pixels, (width, height))
+    }
+
+    /// Save the render area, as it is drawn to the window, to a PNG
+    /// at `path`.
+    pub fn screenshot(&self, path: impl AsRef<Path>) -> Result<(), ResourceError> {
+        save_image(path, &self.frame())
+    }
+    /// Save the whole world layer to a PNG at `path`.
+    pub fn save_world(&self, path: impl AsRef<Path>) -> Result<(), ResourceError> {
+        self.save_layer(WORLD_LAYER, path)
+    }
+    /// Save the whole layer named `name` to a PNG at `path`.
+    ///
+    /// Panics if there is no such layer.
+    pub fn save_layer(&self, name: &str, path: impl AsRef<Path>) -> Result<(), ResourceError> {
+        let layer = self
+            .layer(name)
+            .unwrap_or_else(|| panic!("There is no layer named {:?}.", name));
+        save_image(path, layer.mat())
+    }
+
+    /// Save every `every`th presented frame, starting with the next
+    /// one, to `dir/00000.png`, `dir/00001.png`, ... The directory
+    /// is created if needed and an ongoing capture is stopped.
+    ///
+    /// The frames are written by another thread, the capture stops
+    /// on the first error, which is returned by
+    /// `Game::stop_capture`.
+    pub fn start_capture(
+        &mut self,
+        dir: impl Into<PathBuf>,
+        every: usize,
+    ) -> Result<(), ResourceError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|source| ResourceError::Io {
+            path: dir.clone(),
+            source,
+        })?;
+        self.capture = Some(Capture::new(dir, every));
+        Ok(())
+    }
+    /// Stop the capture, wait for its frames to be written and
+    /// return how many were, or the error it stopped on. None if
+    /// there is no capture.
+    pub fn stop_capture(&mut self) -> Option<Result<usize, ResourceError>> {
+        self.capture.take().map(Capture::stop)
+    }
+    /// Return true if there is a capture and it didn't stop on an
+    /// error.
+    pub fn capturing(&self) -> bool {
+        self.capture.as_ref().is_some_and(Capture::running)
+    }
+
+    /// Put the index and the render position of the visible layers
+    /// in `order`, from the bottom one to the top one.
+    fn layer_order(&self, order: &mut Vec<(usize, (usize, usize))>) {
+        let camera_pos = self.camera.render_pos();
+        order.clear();
+        order.extend(
+            self.layers
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, layer))| *layer.visible() && *layer.opacity() > 0.)
+                .map(|(i, (_, layer))| {
+                    (i, layer.render_pos(camera_pos, self.render_dims).to_usize())
+                }),
+        );
+        // The stable sort would allocate.
+        order.sort_unstable_by_key(|&(i, _)| (*self.layers[i].1.z_index(), i));
+    }
+
+    /// Composite the layers in `order` into the given rows of
+    /// `pixels` (the RGBA bytes of the render area).
+    fn compose(
+        &self,
+        order: &[(usize, (usize, usize))],
+        rows: impl Iterator<Item = usize>,
+        pixels: &mut [u8],
+    ) {
+        let width = self.render_dims.to_usize().0;
+        for y in rows {
+            let row = &mut pixels[y * width * 4..(y + 1) * width * 4];
+
+            // The usual case: a single opaque layer, its row is copied
+            // as is.
+            if let [(i, pos)] = order {
+                let layer = &self.layers[*i].1;
+                let dims = *layer.mat().dims();
+                if *layer.opacity() >= 1. && pos.1 + y < dims.1 && pos.0 + width <= dims.0 {
+                    let src = &layer.mat().row(pos.1 + y)[pos.0..pos.0 + width];
+                    for (dst, color) in row.chunks_exact_mut(4).zip(src) {
+                        dst.copy_from_slice(&color.to_bytes());
+                    }
+                    continue;
+                }
+            }
+
+            for (x, dst) in row.chunks_exact_mut(4).enumerate() {
+                let mut color = Color::TRANSPARENT;
+                for (i, pos) in order {
+                    let layer = &self.layers[*i].1;
+                    let index = (pos.0 + x, pos.1 + y);
+                    if !layer.mat().has(index) {
+                        continue;
+                    }
+                    let src = layer.mat()[index];
+                    color = if *layer.opacity() >= 1. && (src.a == 255 || color.a == 0) {
+                        src
+                    } else if src.a == 0 {
+                        color
+                    } else {
+                        src.with_opacity(*layer.opacity()).over(color)
+                    };
+                }
+                dst.copy_from_slice(&color.to_bytes());
+            }
+        }
+    }
+
+    /// Turn the Game into a Headless runner that doesn't need a
+    /// window (or a GPU).
+    pub fn headless(self) -> Headless {
+        Headless::new(self)
+    }
+
+    /// Composite the rows of the render area that changed since the
+    /// last call into `pixels` (the RGBA bytes of the render area),
+    /// all of them if the camera or a layer moved. Nothing is
+    /// allocated unless layers were added.
+    pub fn present(&mut self, pixels: &mut [u8]) {
+        let (width, height) = self.render_dims.to_usize();
+        let camera_pos = self.camera.render_pos();
+        let render_dims = self.render_dims;
+
+        let mut moved = self.presented.len() != self.layers.len();
+        self.presented
+            .resize(self.layers.len(), (0, false, 0., (0, 0)));
+        self.rows.clear();
+        self.rows.resize(height, false);
+
+        let mut pixels_touched = 0;
+        for ((_, layer), presented) in self.layers.iter_mut().zip(&mut self.presented) {
+            let pos = layer.render_pos(camera_pos, render_dims).to_usize();
+            let state = (*layer.z_index(), *layer.visible(), *layer.opacity(), pos);
+            moved |= *presented != state;
+            *presented = state;
+
+            pixels_touched += layer.grid_mut().take_touched();
+            for rect in layer.grid_mut().drain_changed() {
+                if state.1 {
+                    let start = rect.min.1.saturating_sub(pos.1);
+                    let end = rect.max.1.saturating_sub(pos.1).min(height);
+                    self.rows[start.min(end)..end].fill(true);
+                }
+            }
+        }
+        if moved {
+            self.rows.fill(true);
+        }
+
+        let rows_uploaded = self.rows.iter().filter(|&&row| row).count();
+        self.stats = FrameStats {
+            pixels_touched,
+            rows_uploaded,
+            pixels_uploaded: rows_uploaded * width,
+        };
+
+        let mut order = std::mem::take(&mut self.order);
+        self.layer_order(&mut order);
+        let rows = &self.rows;
+        self.compose(&order, (0..height).filter(|&y| rows[y]), pixels);
+        self.order = order;
+
+        if let Some(capture) = &mut self.capture {
+            capture.frame(pixels, (width, height));
         }
     }
 }
 
+#[derive(Default)]
 pub struct GameBuilder {
     dims: Option<Vec2>,
     render_dims: Option<Vec2>,
     render_pos: Option<Vec2>,
     title: Option<String>,
     background_color: Option<Color>,
+    timestep: Option<Timestep>,
+    input_map: Option<InputMap>,
+    record: Option<PathBuf>,
+    assets_root: Option<PathBuf>,
+    hot_reload: bool,
 }
 
 impl GameBuilder {
@@ -151,6 +627,11 @@ impl GameBuilder {
             render_pos: None,
             title: None,
             background_color: None,
+            timestep: None,
+            input_map: None,
+            record: None,
+            assets_root: None,
+            hot_reload: false,
         }
     }
 
@@ -170,22 +651,112 @@ impl GameBuilder {
         self.background_color = Some(background_color);
         self
     }
+    pub fn timestep(mut self, timestep: Timestep) -> Self {
+        self.timestep = Some(timestep);
+        self
+    }
+    pub fn input_map(mut self, input_map: InputMap) -> Self {
+        self.input_map = Some(input_map);
+        self
+    }
+    /// Record the input from the start and save it to `path` when
+    /// the window is closed, see `Game::start_recording`.
+    pub fn record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record = Some(path.into());
+        self
+    }
+    /// The directory assets are loaded from, `resources::ASSETS_PATH`
+    /// by default.
+    pub fn assets_root(mut self, assets_root: impl Into<PathBuf>) -> Self {
+        self.assets_root = Some(assets_root.into());
+        self
+    }
+    /// Reload the assets when their files change, they are polled
+    /// twice per second.
+    pub fn hot_reload(mut self, hot_reload: bool) -> Self {
+        self.hot_reload = hot_reload;
+        self
+    }
 
     pub fn build(self) -> Game {
         let dims = self
             .dims
             .expect("Game should have dims, define them with GameBuilder::dims(dims).");
 
-        Game {
+        let mut assets: Assets = self.assets_root.map(Assets::new).unwrap_or_default();
+        assets.set_hot_reload(self.hot_reload, Duration::from_millis(500));
+
+        let render_dims = self.render_dims.unwrap_or(dims);
+
+        let mut game = Game {
             dims,
+            render_dims,
 
-            render_dims: self.render_dims.unwrap_or(dims),
-            render_pos: self.render_pos.unwrap_or(Vec2::ZERO),
+            camera: Camera::new(self.render_pos.unwrap_or(Vec2::ZERO), render_dims, dims),
 
             title: self.title.unwrap_or("Game".to_string()),
 
-            grid: Grid::new(dims.into(), self.background_color),
+            layers: vec![(
+                WORLD_LAYER.to_string(),
+                Layer::new(dims, self.background_color),
+            )],
+            active_layer: 0,
+            presented: Vec::new(),
+            stats: FrameStats::default(),
+            rows: Vec::new(),
+            order: Vec::new(),
             input: WinitInputHelper::new(),
+            input_events: Vec::new(),
+            mouse: None,
+            input_map: self.input_map.unwrap_or_default(),
+            timestep: self.timestep.unwrap_or(Timestep::Variable),
+            recording: None,
+            capture: None,
+            recording_path: None,
+
+            assets,
+        };
+        if let Some(path) = self.record {
+            game.start_recording();
+            game.recording_path = Some(path);
         }
+        game
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::vec2::Vec2;
+
+    use super::{GameBuilder, Layer};
+
+    #[test]
+    fn mouse() {
+        let mut headless = GameBuilder::new()
+            .dims(Vec2(32., 16.))
+            .render_dims(Vec2(8., 8.))
+            .render_pos(Vec2(10., 4.))
+            .build()
+            .headless();
+        headless.game_mut().add_layer("far", {
+            let mut layer = Layer::new(Vec2(32., 16.), None);
+            *layer.parallax_mut() = 0.5;
+            layer
+        });
+
+        headless.run(1, |_, _| {});
+        assert_eq!(headless.game().mouse_render_pos(), None);
+
+        headless.mouse_move((3.5, 2.));
+        headless.run(1, |_, _| {});
+        let game = headless.game();
+        assert_eq!(game.mouse_render_pos(), Some(Vec2(3., 2.)));
+        assert_eq!(game.mouse_world_pos(), Some(Vec2(13., 6.)));
+        assert_eq!(game.mouse_layer_pos("far"), Some(Vec2(8., 4.)));
+        assert_eq!(game.mouse_layer_pos("near"), None);
+
+        headless.mouse_move((8., 2.));
+        headless.run(1, |_, _| {});
+        assert_eq!(headless.game().mouse_world_pos(), None);
     }
 }
@@ -0,0 +1,146 @@
+use std::{
+    path::PathBuf,
+    sync::mpsc::{self, Sender},
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    color::Color,
+    game::frame_from_bytes,
+    mat::Mat,
+    resources::{save_image, ResourceError},
+};
+
+/// Saves every Nth presented frame to a numbered PNG, see
+/// `Game::start_capture`. The frames are encoded and written by
+/// another thread so presenting doesn't wait for them.
+pub(super) struct Capture {
+    every: usize,
+    /// Frames presented since the capture started.
+    presented: usize,
+
+    frames: Option<Sender<Mat<Color>>>,
+    /// Returns the number of frames saved, it stops on the first
+    /// error.
+    writer: Option<JoinHandle<Result<usize, ResourceError>>>,
+}
+
+impl Capture {
+    pub fn new(dir: PathBuf, every: usize) -> Self {
+        let (frames, receiver) = mpsc::channel::<Mat<Color>>();
+        let writer = thread::spawn(move || {
+            let mut saved = 0;
+            for frame in receiver {
+                save_image(dir.join(format!("{:05}.png", saved)), &frame)?;
+                saved += 1;
+            }
+            Ok(saved)
+        });
+
+        Capture {
+            every: every.max(1),
+            presented: 0,
+            frames: Some(frames),
+            writer: Some(writer),
+        }
+    }
+
+    /// Called with every presented frame, sends it to the writer if
+    /// it is its turn.
+    pub fn frame(&mut self, pixels: &[u8], dims: (usize, usize)) {
+        let turn = self.presented.is_multiple_of(self.every);
+        self.presented += 1;
+        if let (true, Some(frames)) = (turn, &self.frames) {
+            // Only fails if the writer stopped on an error, it is
+            // returned by `Capture::stop`.
+            let _ = frames.send(frame_from_bytes(pixels, dims));
+        }
+    }
+
+    /// Return false if the writer stopped on an error.
+    pub fn running(&self) -> bool {
+        self.writer
+            .as_ref()
+            .is_some_and(|writer| !writer.is_finished())
+    }
+
+    /// Wait for the frames sent so far to be written and return how
+    /// many were, or the error the writer stopped on.
+    pub fn stop(mut self) -> Result<usize, ResourceError> {
+        self.frames = None;
+        let writer = self.writer.take().unwrap();
+        writer.join().expect("The capture writer panicked.")
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        self.frames = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        color::Color,
+        game::GameBuilder,
+        mat::Mat,
+        resources::{open_image, ResourceError},
+        vec2::Vec2,
+    };
+
+    #[test]
+    fn capture() {
+        let dir = std::env::temp_dir().join(format!("pixel_game_capture_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut headless = GameBuilder::new()
+            .dims(Vec2(8., 4.))
+            .render_dims(Vec2(4., 4.))
+            .background_color(Color::BLACK)
+            .build()
+            .headless();
+        headless.game_mut().start_capture(&dir, 2).unwrap();
+
+        let pixel = Mat::filled_with(Color::WHITE, (1, 1));
+        let mut frames = Vec::new();
+        for x in 0..5 {
+            headless.run(1, |game, _| {
+                game.clear_dirty();
+                game.image_at(Vec2(x as f64, 0.), &pixel);
+            });
+            frames.push(headless.frame());
+        }
+        assert_eq!(headless.game_mut().stop_capture().unwrap().unwrap(), 3);
+        assert!(!headless.game().capturing());
+
+        for (i, frame) in frames.iter().step_by(2).enumerate() {
+            let saved = open_image(dir.join(format!("{:05}.png", i))).unwrap();
+            assert_eq!(&saved, frame);
+        }
+        assert!(!dir.join("00003.png").exists());
+
+        let game = headless.game();
+        game.screenshot(dir.join("screenshot.png")).unwrap();
+        assert_eq!(
+            open_image(dir.join("screenshot.png")).unwrap(),
+            game.frame()
+        );
+        game.save_world(dir.join("world.png")).unwrap();
+        let world = open_image(dir.join("world.png")).unwrap();
+        assert_eq!(*world.dims(), (8, 4));
+        assert_eq!(world[(4, 0)], Color::WHITE);
+
+        // The directory is gone, the writer stops on the error.
+        headless.game_mut().start_capture(&dir, 1).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        headless.run(2, |_, _| {});
+        assert!(matches!(
+            headless.game_mut().stop_capture(),
+            Some(Err(ResourceError::Io { .. }))
+        ));
+    }
+}
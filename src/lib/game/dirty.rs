@@ -0,0 +1,150 @@
+/// Past this number of rectangles they are merged into their
+/// bounding box, checking them would cost more than it saves.
+const MAX_RECTS: usize = 16;
+
+/// An area of a Grid, `max` is excluded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Rect {
+    pub min: (usize, usize),
+    pub max: (usize, usize),
+}
+
+impl Rect {
+    pub fn new(pos: (usize, usize), dims: (usize, usize)) -> Self {
+        Rect {
+            min: pos,
+            max: (pos.0 + dims.0, pos.1 + dims.1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.0 >= self.max.0 || self.min.1 >= self.max.1
+    }
+
+    pub fn area(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            (self.max.0 - self.min.0) * (self.max.1 - self.min.1)
+        }
+    }
+
+    /// Return true if the rectangles overlap or share an edge.
+    fn touches(&self, other: &Rect) -> bool {
+        self.min.0 <= other.max.0
+            && other.min.0 <= self.max.0
+            && self.min.1 <= other.max.1
+            && other.min.1 <= self.max.1
+    }
+
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            min: (self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: (self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+}
+
+/// The regions of a Grid that changed, touching rectangles are
+/// merged.
+#[derive(Default)]
+pub(crate) struct DirtyRects(Vec<Rect>);
+
+impl DirtyRects {
+    pub fn add(&mut self, mut rect: Rect) {
+        if rect.is_empty() {
+            return;
+        }
+        while let Some(i) = self.0.iter().position(|r| r.touches(&rect)) {
+            rect = rect.union(&self.0.swap_remove(i));
+        }
+        self.0.push(rect);
+
+        if self.0.len() > MAX_RECTS {
+            let bounds = self.0.iter().fold(rect, |bounds, r| bounds.union(r));
+            self.0 = vec![bounds];
+        }
+    }
+
+    /// Remove the rectangles and return them, the memory is kept
+    /// for the next ones.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, Rect> {
+        self.0.drain(..)
+    }
+
+    #[cfg(test)]
+    pub fn rects(&self) -> &[Rect] {
+        &self.0
+    }
+}
+
+/// What the last frame cost, see `Game::frame_stats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Pixels written to the layers (drawn or cleared).
+    pub pixels_touched: usize,
+    /// Rows of the render area composited and copied to the window.
+    pub rows_uploaded: usize,
+    pub pixels_uploaded: usize,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{color::Color, game::GameBuilder, mat::Mat, vec2::Vec2};
+
+    use super::{DirtyRects, Rect, MAX_RECTS};
+
+    #[test]
+    fn merge() {
+        let mut rects = DirtyRects::default();
+        rects.add(Rect::new((0, 0), (2, 2)));
+        rects.add(Rect::new((5, 5), (2, 2)));
+        rects.add(Rect::new((0, 0), (0, 4)));
+        assert_eq!(rects.rects().len(), 2);
+
+        // Shares an edge with the first one, then overlaps both.
+        rects.add(Rect::new((2, 0), (1, 1)));
+        assert_eq!(
+            rects.rects(),
+            [Rect::new((5, 5), (2, 2)), Rect::new((0, 0), (3, 2))]
+        );
+        rects.add(Rect::new((1, 1), (5, 5)));
+        assert_eq!(rects.rects(), [Rect::new((0, 0), (7, 7))]);
+
+        for i in 0..MAX_RECTS {
+            rects.add(Rect::new((10 + 2 * i, 0), (1, 1)));
+        }
+        assert!(rects.drain().eq([Rect::new((0, 0), (41, 7))]));
+        assert!(rects.rects().is_empty());
+    }
+
+    #[test]
+    fn dirty_rows() {
+        let mut headless = GameBuilder::new()
+            .dims(Vec2(8., 8.))
+            .background_color(Color::BLACK)
+            .build()
+            .headless();
+        let block = Mat::filled_with(Color::WHITE, (2, 2));
+
+        headless.run(1, |game, _| {
+            game.image_at(Vec2(1., 1.), &block);
+        });
+        assert_eq!(headless.game().frame_stats().rows_uploaded, 8);
+
+        headless.run(1, |game, _| {
+            game.clear_dirty();
+            game.image_at(Vec2(1., 5.), &block);
+        });
+        let stats = *headless.game().frame_stats();
+        assert_eq!(stats.pixels_touched, 8);
+        assert_eq!(stats.rows_uploaded, 4);
+        assert_eq!(stats.pixels_uploaded, 32);
+        assert_eq!(headless.frame()[(1, 1)], Color::BLACK);
+        assert_eq!(headless.frame()[(2, 6)], Color::WHITE);
+        assert_eq!(headless.frame(), headless.game().frame());
+
+        headless.run(1, |_, _| {});
+        assert_eq!(headless.game().frame_stats().rows_uploaded, 0);
+    }
+}
@@ -1,33 +1,186 @@
-use crate::{color::Color, mat::Mat, vec2::Vec2};
+use crate::{
+    color::{BlendMode, Color},
+    draw::Painter,
+    game::dirty::{DirtyRects, Rect},
+    mat::{Mat, MatSlice, MatSliceMut},
+    vec2::Vec2,
+};
 
 pub struct Grid {
     background_color: Color,
     mat: Mat<Color>,
     dims: Vec2,
+
+    /// Regions drawn since the last `Grid::clear_dirty`.
+    drawn: DirtyRects,
+    /// Regions changed since the last `Grid::take_changed`.
+    changed: DirtyRects,
+    /// Pixels written since the last `Grid::take_touched`.
+    touched: usize,
 }
 
 impl Grid {
     pub fn new(dims: Vec2, background_color: Option<Color>) -> Self {
         let background_color = background_color.unwrap_or(Color::TRANSPARENT);
+        // Nothing of it was ever presented.
+        let mut changed = DirtyRects::default();
+        changed.add(Rect::new((0, 0), dims.to_usize()));
         Grid {
             background_color,
             mat: Mat::filled_with(background_color, dims.to_usize()),
             dims,
+            drawn: DirtyRects::default(),
+            changed,
+            touched: 0,
+        }
+    }
+
+    /// The part of the area starting at `index` with dimensions
+    /// `dims` that is inside the Grid.
+    fn clip(&self, index: Vec2, dims: Vec2) -> Rect {
+        let start = Vec2(index.0.max(0.), index.1.max(0.));
+        let end = Vec2(
+            (index.0 + dims.0).min(self.dims.0),
+            (index.1 + dims.1).min(self.dims.1),
+        );
+        if start.0 < end.0 && start.1 < end.1 {
+            Rect::new(start.to_usize(), (end - start).to_usize())
+        } else {
+            Rect::new((0, 0), (0, 0))
         }
     }
 
-    pub fn clear_pixels(&mut self, pixels: &Vec<Vec2>) {
+    /// Remember that `rect` was drawn on.
+    fn mark(&mut self, rect: Rect) {
+        self.drawn.add(rect);
+        self.changed.add(rect);
+        self.touched += rect.area();
+    }
+
+    /// Draw `image` at `pos`, the pixels after `max` are skipped
+    /// (the ones at `max` are drawn). See `Game::image_at_blend`.
+    pub fn draw(
+        &mut self,
+        pos: Vec2,
+        image: &impl MatSlice<Color>,
+        mode: BlendMode,
+        opacity: f64,
+        max: Vec2,
+    ) -> Vec<Vec2> {
+        let image_dims = image.slice_dims();
+
+        let mut changed_pixels = Vec::new();
+        let mut bounds: Option<Rect> = None;
+        for x in 0..image_dims.0 {
+            for y in 0..image_dims.1 {
+                let index: Vec2 = pos + Vec2::from_usize(x, y);
+
+                if 0. <= index.0 && 0. <= index.1 && index.0 <= max.0 && index.1 <= max.1 {
+                    let pixel = image[(x, y)];
+                    let skipped = match mode {
+                        BlendMode::Opaque => pixel.a != 255,
+                        _ => pixel.a == 0,
+                    };
+                    let index_usize = index.to_usize();
+                    if !skipped && self.mat.has(index_usize) {
+                        let dst = &mut self.mat[index_usize];
+                        *dst = match mode {
+                            BlendMode::Opaque if opacity >= 1. => pixel,
+                            BlendMode::Opaque => pixel.with_opacity(opacity).over(*dst),
+                            _ => pixel.with_opacity(opacity).blend(*dst, mode),
+                        };
+                        changed_pixels.push(index);
+
+                        let pixel_rect = Rect::new(index_usize, (1, 1));
+                        bounds = Some(bounds.map_or(pixel_rect, |b| b.union(&pixel_rect)));
+                    }
+                }
+            }
+        }
+
+        if let Some(bounds) = bounds {
+            self.drawn.add(bounds);
+            self.changed.add(bounds);
+        }
+        self.touched += changed_pixels.len();
+        changed_pixels
+    }
+
+    /// A Painter drawing on the area starting at `pos` with
+    /// dimensions `dims`, the whole area counts as drawn.
+    pub fn painter(&mut self, pos: Vec2, dims: Vec2) -> Painter<'_> {
+        self.mark(self.clip(pos, dims));
+        Painter::clipped(&mut self.mat, pos, dims)
+    }
+
+    pub fn clear_pixels(&mut self, pixels: &[Vec2]) {
         for index in pixels {
             self.mat[(*index).to_usize()] = self.background_color;
+            self.changed.add(Rect::new((*index).to_usize(), (1, 1)));
+        }
+        self.touched += pixels.len();
+    }
+
+    /// Reset the pixels of the area starting at `index` with
+    /// dimensions `dims` to the background color, the part of the
+    /// area outside of the Grid is ignored.
+    pub fn clear_region(&mut self, index: Vec2, dims: Vec2) {
+        let rect = self.clip(index, dims);
+        self.reset(rect);
+    }
+
+    fn reset(&mut self, rect: Rect) {
+        if !rect.is_empty() {
+            self.mat
+                .slice_mut(
+                    rect.min,
+                    (rect.max.0 - rect.min.0, rect.max.1 - rect.min.1),
+                    (false, false),
+                )
+                .fill(self.background_color);
+            self.changed.add(rect);
+            self.touched += rect.area();
         }
     }
 
+    /// Reset what was drawn since the last call to the background
+    /// color, which is cheaper than clearing the whole Grid when
+    /// only a few things move.
+    pub fn clear_dirty(&mut self) {
+        // Taken out so reset can borrow self, then put back so its
+        // memory is reused.
+        let mut drawn = std::mem::take(&mut self.drawn);
+        for rect in drawn.drain() {
+            self.reset(rect);
+        }
+        self.drawn = drawn;
+    }
+
+    /// Fill the whole Grid with `color`.
+    pub fn fill(&mut self, color: Color) {
+        self.mat.fill(color);
+        self.mark(Rect::new((0, 0), self.dims.to_usize()));
+    }
+
+    /// Remove the regions changed since the last call and return
+    /// them.
+    pub fn drain_changed(&mut self) -> impl Iterator<Item = Rect> + '_ {
+        self.changed.drain()
+    }
+
+    /// Return the number of pixels written since the last call.
+    pub fn take_touched(&mut self) -> usize {
+        std::mem::take(&mut self.touched)
+    }
+
     pub fn mat(&self) -> &Mat<Color> {
         &self.mat
     }
     // I could do a joke but I won't. Hint: France. Happy
     // googling.
     pub fn mat_mut(&mut self) -> &mut Mat<Color> {
+        // Anything can be changed.
+        self.mark(Rect::new((0, 0), self.dims.to_usize()));
         &mut self.mat
     }
 
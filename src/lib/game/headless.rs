@@ -0,0 +1,274 @@
+use winit::{
+    dpi::PhysicalPosition,
+    event::{
+        DeviceId, ElementState, KeyboardInput, ModifiersState, MouseButton, VirtualKeyCode,
+        WindowEvent,
+    },
+};
+use winit_input_helper::WinitInputHelper;
+
+use crate::{
+    color::Color,
+    game::{frame_from_bytes, Game, InputEvent, Recording},
+    input::Binding,
+    mat::Mat,
+};
+
+fn device_id() -> DeviceId {
+    // The id is never compared against a real device, the input
+    // helper doesn't even look at it.
+    unsafe { DeviceId::dummy() }
+}
+
+/// Runs a Game without any window: the update closure is called
+/// once per frame and input events can be injected between
+/// frames so `Game::input` works as usual.
+///
+/// Every frame is exactly one tick, dt is the tick duration of
+/// the Game's fixed timestep (or 1/60s with a variable one). The
+/// window is as big as the render area, one window pixel is one
+/// render pixel.
+pub struct Headless {
+    game: Game,
+    events: Vec<WindowEvent<'static>>,
+    /// The input of the window, the updates see `Game::input`.
+    window_input: WinitInputHelper,
+    frame_count: usize,
+    /// Stands for the window's frame buffer.
+    pixels: Vec<u8>,
+}
+
+impl Headless {
+    pub fn new(game: Game) -> Self {
+        let (width, height) = game.render_dims.to_usize();
+        Headless {
+            game,
+            events: Vec::new(),
+            window_input: WinitInputHelper::new(),
+            frame_count: 0,
+            pixels: vec![0; width * height * 4],
+        }
+    }
+
+    /// Run `frames` frames, the injected events are applied
+    /// before the first one.
+    pub fn run<U>(&mut self, frames: usize, mut update: U)
+    where
+        U: FnMut(&mut Game, f64),
+    {
+        let dt = self.dt();
+        for _ in 0..frames {
+            self.run_frame(1, dt, &mut update);
+        }
+    }
+
+    /// Run the frames of `recording`, its presses, releases and
+    /// cursor pixels are injected in place of the events (see
+    /// `Recording` for the input that isn't recorded).
+    pub fn replay<U>(&mut self, recording: &Recording, mut update: U)
+    where
+        U: FnMut(&mut Game, f64),
+    {
+        for frame in recording.frames() {
+            for event in &frame.events {
+                match *event {
+                    InputEvent::Press(binding) => self.push_binding(binding, ElementState::Pressed),
+                    InputEvent::Release(binding) => {
+                        self.push_binding(binding, ElementState::Released)
+                    }
+                    // The center of the pixel, the window is the
+                    // render area.
+                    InputEvent::Cursor(Some((x, y))) => {
+                        self.mouse_move((x as f64 + 0.5, y as f64 + 0.5))
+                    }
+                    InputEvent::Cursor(None) => self.mouse_move((-1., -1.)),
+                }
+            }
+            self.run_frame(frame.ticks, recording.dt(), &mut update);
+        }
+    }
+
+    /// Apply the injected events, run `ticks` updates and present
+    /// the Game.
+    fn run_frame<U>(&mut self, ticks: usize, dt: f64, update: &mut U)
+    where
+        U: FnMut(&mut Game, f64),
+    {
+        self.game.assets.poll();
+        if let Some(recording) = &mut self.game.recording {
+            for event in &self.events {
+                recording.record_event(event);
+            }
+        }
+        self.window_input.step_with_window_events(&self.events);
+        self.game.input_events.append(&mut self.events);
+        self.game.mouse = self.mouse_pixel();
+
+        for _ in 0..ticks {
+            self.game.step_input();
+            self.game.camera.update(dt);
+            update(&mut self.game, dt);
+        }
+        if let Some(recording) = &mut self.game.recording {
+            recording.end_frame(ticks, self.game.mouse);
+        }
+        self.game.present(&mut self.pixels);
+
+        self.frame_count += 1;
+    }
+
+    /// Inject a raw winit event, it will be seen by the next
+    /// frame.
+    pub fn push_event(&mut self, event: WindowEvent<'static>) {
+        self.events.push(event);
+    }
+
+    #[allow(deprecated)]
+    fn push_binding(&mut self, binding: Binding, state: ElementState) {
+        self.push_event(match binding {
+            Binding::Key(key) => WindowEvent::KeyboardInput {
+                device_id: device_id(),
+                input: KeyboardInput {
+                    scancode: 0,
+                    state,
+                    virtual_keycode: Some(key),
+                    modifiers: ModifiersState::empty(),
+                },
+                is_synthetic: true,
+            },
+            Binding::Mouse(button) => WindowEvent::MouseInput {
+                device_id: device_id(),
+                state,
+                button,
+                modifiers: ModifiersState::empty(),
+            },
+        });
+    }
+    pub fn key_down(&mut self, key: VirtualKeyCode) {
+        self.push_binding(Binding::Key(key), ElementState::Pressed);
+    }
+    pub fn key_up(&mut self, key: VirtualKeyCode) {
+        self.push_binding(Binding::Key(key), ElementState::Released);
+    }
+    pub fn mouse_down(&mut self, button: MouseButton) {
+        self.push_binding(Binding::Mouse(button), ElementState::Pressed);
+    }
+    pub fn mouse_up(&mut self, button: MouseButton) {
+        self.push_binding(Binding::Mouse(button), ElementState::Released);
+    }
+
+    /// Move the cursor to `pos` (in window coordinates).
+    #[allow(deprecated)]
+    pub fn mouse_move(&mut self, pos: (f64, f64)) {
+        self.push_event(WindowEvent::CursorMoved {
+            device_id: device_id(),
+            position: PhysicalPosition::new(pos.0, pos.1),
+            modifiers: ModifiersState::empty(),
+        });
+    }
+
+    /// The render area pixel under the cursor.
+    fn mouse_pixel(&self) -> Option<(usize, usize)> {
+        let (x, y) = self.window_input.mouse()?;
+        let (width, height) = self.game.render_dims.to_usize();
+        let pixel = (x.floor() as usize, y.floor() as usize);
+        (x >= 0. && y >= 0. && pixel.0 < width && pixel.1 < height).then_some(pixel)
+    }
+
+    /// The dt passed to the update closure.
+    pub fn dt(&self) -> f64 {
+        self.game.timestep.dt().unwrap_or(1. / 60.)
+    }
+
+    /// The last frame, as it was drawn to the window (only the rows
+    /// that changed are drawn again every frame).
+    pub fn frame(&self) -> Mat<Color> {
+        frame_from_bytes(&self.pixels, self.game.render_dims.to_usize())
+    }
+
+    /// The number of frames run so far.
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+    pub fn game_mut(&mut self) -> &mut Game {
+        &mut self.game
+    }
+    pub fn into_game(self) -> Game {
+        self.game
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use winit::event::VirtualKeyCode;
+
+    use crate::{
+        color::Color,
+        game::{Game, GameBuilder, Timestep},
+        mat::Mat,
+        vec2::Vec2,
+    };
+
+    #[test]
+    fn run() {
+        let mut headless = GameBuilder::new()
+            .dims(Vec2(4., 2.))
+            .background_color(Color::BLACK)
+            .build()
+            .headless();
+
+        let pixel = Mat::filled_with(Color::WHITE, (1, 1));
+        let mut x = 0.;
+        let mut update = |game: &mut crate::game::Game, _| {
+            if game.input().key_held(VirtualKeyCode::Right) {
+                x += 1.;
+            }
+            game.clear(Color::BLACK);
+            game.image_at(Vec2(x, 0.), &pixel);
+        };
+
+        headless.run(1, &mut update);
+        assert_eq!(headless.frame()[(0, 0)], Color::WHITE);
+
+        headless.key_down(VirtualKeyCode::Right);
+        headless.run(2, &mut update);
+        headless.key_up(VirtualKeyCode::Right);
+        headless.run(1, &mut update);
+
+        let frame = headless.frame();
+        assert_eq!(headless.frame_count(), 4);
+        assert_eq!(frame[(0, 0)], Color::BLACK);
+        assert_eq!(frame[(2, 0)], Color::WHITE);
+        assert_eq!(*frame.dims(), (4, 2));
+    }
+
+    #[test]
+    fn press_between_ticks() {
+        let mut headless = GameBuilder::new()
+            .dims(Vec2(4., 4.))
+            .timestep(Timestep::fixed(60.))
+            .build()
+            .headless();
+        let dt = headless.dt();
+        let (mut pressed, mut released) = (0, 0);
+        let mut update = |game: &mut Game, _| {
+            pressed += game.input().key_pressed(VirtualKeyCode::Space) as usize;
+            released += game.input().key_released(VirtualKeyCode::Space) as usize;
+        };
+
+        // A frame too short for a tick, the press waits for the
+        // first tick of the next frame.
+        headless.key_down(VirtualKeyCode::Space);
+        headless.run_frame(0, dt, &mut update);
+        headless.run_frame(2, dt, &mut update);
+        headless.key_up(VirtualKeyCode::Space);
+        headless.run_frame(0, dt, &mut update);
+        headless.run_frame(0, dt, &mut update);
+        headless.run_frame(2, dt, &mut update);
+        assert_eq!((pressed, released), (1, 1));
+    }
+}
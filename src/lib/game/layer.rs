@@ -0,0 +1,112 @@
+use crate::{color::Color, draw::Painter, game::grid::Grid, mat::Mat, vec2::Vec2};
+
+/// The name of the layer every Game starts with.
+pub const WORLD_LAYER: &str = "world";
+
+/// A Mat drawn on top of (or under) the other layers of the Game.
+pub struct Layer {
+    grid: Grid,
+    /// Layers are composited from the lowest z-index to the
+    /// highest one, layers with the same z-index in the order they
+    /// were added.
+    z_index: i32,
+    visible: bool,
+    /// Between 0 and 1, multiplied with the alpha of every pixel.
+    opacity: f64,
+    /// How much the layer moves with the camera: 1 for the world,
+    /// less for a distant background and 0 for the UI.
+    parallax: f64,
+}
+
+impl Layer {
+    /// Create a visible and opaque layer that moves with the
+    /// camera, it is transparent by default.
+    pub fn new(dims: Vec2, background_color: Option<Color>) -> Self {
+        Layer {
+            grid: Grid::new(dims, background_color),
+            z_index: 0,
+            visible: true,
+            opacity: 1.,
+            parallax: 1.,
+        }
+    }
+
+    /// The position of the render area in the layer.
+    pub(super) fn render_pos(&self, camera_pos: Vec2, render_dims: Vec2) -> Vec2 {
+        let dims = *self.dims();
+        let pos = camera_pos * self.parallax;
+        Vec2(
+            pos.0.floor().clamp(0., (dims.0 - render_dims.0).max(0.)),
+            pos.1.floor().clamp(0., (dims.1 - render_dims.1).max(0.)),
+        )
+    }
+
+    /// A Painter drawing anywhere on the layer.
+    pub fn painter(&mut self) -> Painter<'_> {
+        let dims = *self.grid.dims();
+        self.grid.painter(Vec2::ZERO, dims)
+    }
+
+    pub(super) fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    pub fn mat(&self) -> &Mat<Color> {
+        self.grid.mat()
+    }
+    pub fn mat_mut(&mut self) -> &mut Mat<Color> {
+        self.grid.mat_mut()
+    }
+    pub fn dims(&self) -> &Vec2 {
+        self.grid.dims()
+    }
+
+    pub fn z_index(&self) -> &i32 {
+        &self.z_index
+    }
+    pub fn z_index_mut(&mut self) -> &mut i32 {
+        &mut self.z_index
+    }
+    pub fn visible(&self) -> &bool {
+        &self.visible
+    }
+    pub fn visible_mut(&mut self) -> &mut bool {
+        &mut self.visible
+    }
+    pub fn opacity(&self) -> &f64 {
+        &self.opacity
+    }
+    pub fn opacity_mut(&mut self) -> &mut f64 {
+        &mut self.opacity
+    }
+    pub fn parallax(&self) -> &f64 {
+        &self.parallax
+    }
+    pub fn parallax_mut(&mut self) -> &mut f64 {
+        &mut self.parallax
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{color::Color, game::GameBuilder, vec2::Vec2};
+
+    use super::{Layer, WORLD_LAYER};
+
+    #[test]
+    fn replace_layer() {
+        let mut headless = GameBuilder::new()
+            .dims(Vec2(4., 4.))
+            .background_color(Color::BLACK)
+            .build()
+            .headless();
+        headless.run(2, |_, _| {});
+        assert_eq!(headless.frame()[(1, 1)], Color::BLACK);
+
+        headless.run(1, |game, _| {
+            game.add_layer(WORLD_LAYER, Layer::new(Vec2(4., 4.), Some(Color::WHITE)));
+        });
+        assert_eq!(headless.frame()[(1, 1)], Color::WHITE);
+        assert_eq!(headless.frame(), headless.game().frame());
+    }
+}
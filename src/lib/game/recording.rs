@@ -0,0 +1,318 @@
+use std::{fmt::Display, path::Path};
+
+use winit::event::{ElementState, WindowEvent};
+
+use crate::{input::Binding, resources::ResourceError};
+
+/// Something the player did between two frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputEvent {
+    Press(Binding),
+    Release(Binding),
+    /// The cursor moved to this render area pixel, None when it
+    /// left the render area.
+    Cursor(Option<(usize, usize)>),
+}
+
+impl InputEvent {
+    /// The InputEvent of a winit event, None for the events that
+    /// aren't recorded (cursor moves are recorded by
+    /// `Recording::end_frame` once mapped to the render area).
+    pub fn from_window_event(event: &WindowEvent) -> Option<Self> {
+        let (binding, state) = match event {
+            WindowEvent::KeyboardInput { input, .. } => {
+                (Binding::Key(input.virtual_keycode?), input.state)
+            }
+            WindowEvent::MouseInput { button, state, .. } => (Binding::Mouse(*button), *state),
+            _ => return None,
+        };
+        Some(match state {
+            ElementState::Pressed => InputEvent::Press(binding),
+            ElementState::Released => InputEvent::Release(binding),
+        })
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        if let Some(binding) = name.strip_prefix('+') {
+            Binding::from_name(binding).map(InputEvent::Press)
+        } else if let Some(binding) = name.strip_prefix('-') {
+            Binding::from_name(binding).map(InputEvent::Release)
+        } else if name == "@-" {
+            Some(InputEvent::Cursor(None))
+        } else {
+            let (x, y) = name.strip_prefix('@')?.split_once(',')?;
+            Some(InputEvent::Cursor(Some((x.parse().ok()?, y.parse().ok()?))))
+        }
+    }
+}
+
+impl Display for InputEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputEvent::Press(binding) => write!(f, "+{}", binding),
+            InputEvent::Release(binding) => write!(f, "-{}", binding),
+            InputEvent::Cursor(Some((x, y))) => write!(f, "@{},{}", x, y),
+            InputEvent::Cursor(None) => write!(f, "@-"),
+        }
+    }
+}
+
+/// The input of a frame and the number of ticks run after it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecordedFrame {
+    pub events: Vec<InputEvent>,
+    pub ticks: usize,
+}
+
+/// The input of a game session, frame by frame, replayed with
+/// `Headless::replay`. Only the presses and releases of keys and
+/// mouse buttons and the render area pixel under the cursor are
+/// recorded: what the actions of the InputMap, the key and mouse
+/// button queries of `Game::input` and the `Game::mouse_*_pos`
+/// methods see. Scrolling, typed text, modifiers and the exact
+/// cursor position aren't, an update closure reading them sees
+/// something else in a replay.
+///
+/// It is saved as text, the dt on the first line then one frame
+/// per line: the number of ticks followed by the events.
+/// ```text
+/// dt 0.016666666666666666
+/// 1 +Space @12,40
+/// 2 -Space
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    dt: f64,
+    frames: Vec<RecordedFrame>,
+
+    /// The events of the frame being recorded.
+    pending: Vec<InputEvent>,
+    mouse: Option<(usize, usize)>,
+}
+
+impl Recording {
+    /// Create an empty Recording of a game updated with a fixed
+    /// `dt`.
+    pub fn new(dt: f64) -> Self {
+        Recording {
+            dt,
+            frames: Vec::new(),
+            pending: Vec::new(),
+            mouse: None,
+        }
+    }
+
+    /// Record `event` if it is an input, see
+    /// `InputEvent::from_window_event`.
+    pub fn record_event(&mut self, event: &WindowEvent) {
+        if let Some(event) = InputEvent::from_window_event(event) {
+            self.pending.push(event);
+        }
+    }
+
+    /// End the frame being recorded, `ticks` updates were run
+    /// after its input and `mouse` is the render area pixel under
+    /// the cursor.
+    pub fn end_frame(&mut self, ticks: usize, mouse: Option<(usize, usize)>) {
+        let mut events = std::mem::take(&mut self.pending);
+        if mouse != self.mouse {
+            events.push(InputEvent::Cursor(mouse));
+            self.mouse = mouse;
+        }
+        self.frames.push(RecordedFrame { events, ticks });
+    }
+
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+    /// The number of ticks of the whole Recording.
+    pub fn ticks(&self) -> usize {
+        self.frames.iter().map(|frame| frame.ticks).sum()
+    }
+
+    /// Parse a Recording from the content of a file, `path` is only
+    /// used in errors.
+    pub fn parse(path: impl AsRef<Path>, content: &str) -> Result<Self, ResourceError> {
+        let error = |line: usize, message: String| ResourceError::Parse {
+            path: path.as_ref().to_owned(),
+            line,
+            message,
+        };
+
+        let mut lines = content.lines().enumerate();
+        let dt = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix("dt "))
+            .and_then(|dt| dt.trim().parse().ok())
+            .ok_or_else(|| error(1, "expected `dt <seconds>`".to_string()))?;
+
+        let mut recording = Recording::new(dt);
+        for (i, line) in lines {
+            let mut words = line.split_whitespace();
+            let Some(ticks) = words.next() else {
+                continue;
+            };
+            let ticks = ticks
+                .parse()
+                .map_err(|_| error(i + 1, format!("invalid tick count {:?}", ticks)))?;
+            let events = words
+                .map(|word| {
+                    InputEvent::from_name(word)
+                        .ok_or_else(|| error(i + 1, format!("invalid event {:?}", word)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            for event in &events {
+                if let InputEvent::Cursor(mouse) = event {
+                    recording.mouse = *mouse;
+                }
+            }
+            recording.frames.push(RecordedFrame { events, ticks });
+        }
+        Ok(recording)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ResourceError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|source| ResourceError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        Recording::parse(path, &content)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ResourceError> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string()).map_err(|source| ResourceError::Io {
+            path: path.to_owned(),
+            source,
+        })
+    }
+}
+
+impl Display for Recording {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Debug prints the shortest representation that parses
+        // back to the same f64.
+        writeln!(f, "dt {:?}", self.dt)?;
+        for frame in &self.frames {
+            write!(f, "{}", frame.ticks)?;
+            for event in &frame.events {
+                write!(f, " {}", event)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use winit::event::{MouseButton, VirtualKeyCode};
+
+    use crate::{
+        game::{Game, GameBuilder, Headless, Timestep},
+        input::Binding,
+        object::Object,
+        physics::Physics,
+        resources::ResourceError,
+        vec2::Vec2,
+    };
+
+    use super::{InputEvent, Recording};
+
+    #[test]
+    fn parse() {
+        let recording =
+            Recording::parse("replay.txt", "dt 0.5\n1 +Space @3,4\n0\n2 -MouseLeft @-\n").unwrap();
+        assert_eq!(recording.dt(), 0.5);
+        assert_eq!(recording.ticks(), 3);
+        assert_eq!(
+            recording.frames()[0].events,
+            [
+                InputEvent::Press(Binding::Key(VirtualKeyCode::Space)),
+                InputEvent::Cursor(Some((3, 4)))
+            ]
+        );
+        assert_eq!(
+            recording.frames()[2].events,
+            [
+                InputEvent::Release(Binding::Mouse(MouseButton::Left)),
+                InputEvent::Cursor(None)
+            ]
+        );
+        assert_eq!(
+            recording.to_string(),
+            "dt 0.5\n1 +Space @3,4\n0\n2 -MouseLeft @-\n"
+        );
+
+        for content in ["1 +Space", "dt 0.5\n1 +Spice", "dt 0.5\nx"] {
+            assert!(matches!(
+                Recording::parse("replay.txt", content),
+                Err(ResourceError::Parse { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn replay() {
+        fn session() -> (Headless, (Physics, Object)) {
+            let game = GameBuilder::new()
+                .dims(Vec2(64., 64.))
+                .timestep(Timestep::fixed(60.))
+                .build();
+            let object = Object::new(Vec2(10., 10.), Vec2(2., 2.), None);
+            let physics = Physics::new(*object.pos(), Vec2::ZERO, 1., 9.81);
+            (game.headless(), (physics, object))
+        }
+        fn update((physics, object): &mut (Physics, Object)) -> impl FnMut(&mut Game, f64) + '_ {
+            move |game, dt| {
+                physics.set_tf_to_w();
+                if game.input().key_held(VirtualKeyCode::Right) {
+                    physics.apply_force(Vec2(30., 0.));
+                }
+                if game.input().key_pressed(VirtualKeyCode::Space) {
+                    *physics.v_mut() += Vec2(0., -20.);
+                }
+                if let Some(pos) = game.mouse_world_pos() {
+                    if game.input().mouse_pressed(0) {
+                        *physics.pos_mut() = pos;
+                    }
+                }
+                physics.update(dt);
+                *object.pos_mut() = *physics.pos();
+            }
+        }
+
+        let (mut headless, mut state) = session();
+        headless.game_mut().start_recording();
+        headless.key_down(VirtualKeyCode::Right);
+        headless.run(10, update(&mut state));
+        headless.key_down(VirtualKeyCode::Space);
+        headless.mouse_move((20.7, 3.2));
+        headless.run(1, update(&mut state));
+        headless.key_up(VirtualKeyCode::Space);
+        headless.mouse_down(MouseButton::Left);
+        headless.run(5, update(&mut state));
+        headless.key_up(VirtualKeyCode::Right);
+        headless.mouse_move((70., 3.));
+        headless.run(20, update(&mut state));
+
+        let recording = headless.game_mut().stop_recording().unwrap();
+        assert_eq!(recording.ticks(), 36);
+        let recording = Recording::parse("replay.txt", &recording.to_string()).unwrap();
+
+        let (mut replayed, mut replayed_state) = session();
+        replayed.game_mut().start_recording();
+        replayed.replay(&recording, update(&mut replayed_state));
+        assert_eq!(replayed.game().recording(), Some(&recording));
+
+        let bits = |(physics, object): &(Physics, Object)| {
+            let (pos, v) = (object.pos(), physics.v());
+            [pos.0, pos.1, v.0, v.1].map(f64::to_bits)
+        };
+        assert_eq!(bits(&replayed_state), bits(&state));
+    }
+}
@@ -0,0 +1,126 @@
+/// How the game loop advances time between two calls to the
+/// update closure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timestep {
+    /// Update once per frame, dt is the time elapsed since the
+    /// previous frame.
+    Variable,
+    /// Update `tick_rate` times per second with a constant dt,
+    /// running at most `max_steps` catch-up updates per frame.
+    Fixed { tick_rate: f64, max_steps: usize },
+}
+
+impl Timestep {
+    /// Fixed timestep with `tick_rate` updates per second and a
+    /// default cap of 5 catch-up updates per frame.
+    pub fn fixed(tick_rate: f64) -> Self {
+        Timestep::Fixed {
+            tick_rate,
+            max_steps: 5,
+        }
+    }
+
+    /// The duration of a tick in seconds, None for a variable
+    /// timestep.
+    pub fn dt(&self) -> Option<f64> {
+        match self {
+            Timestep::Variable => None,
+            Timestep::Fixed { tick_rate, .. } => Some(1. / tick_rate),
+        }
+    }
+}
+
+/// What to do for a frame: run `steps` updates with `dt` then
+/// render with the interpolation factor `alpha`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ticks {
+    pub steps: usize,
+    pub dt: f64,
+    pub alpha: f64,
+}
+
+/// Accumulates elapsed time and turns it into Ticks.
+#[derive(Debug, Clone)]
+pub struct Accumulator {
+    timestep: Timestep,
+    accumulated: f64,
+}
+
+impl Accumulator {
+    pub fn new(timestep: Timestep) -> Self {
+        Accumulator {
+            timestep,
+            accumulated: 0.,
+        }
+    }
+
+    /// Add `elapsed` seconds and return the ticks to run.
+    pub fn advance(&mut self, elapsed: f64) -> Ticks {
+        match self.timestep {
+            Timestep::Variable => Ticks {
+                steps: 1,
+                dt: elapsed,
+                alpha: 1.,
+            },
+            Timestep::Fixed { max_steps, .. } => {
+                let dt = self.timestep.dt().unwrap();
+                self.accumulated += elapsed;
+
+                let mut steps = 0;
+                while self.accumulated >= dt && steps < max_steps {
+                    self.accumulated -= dt;
+                    steps += 1;
+                }
+                // Too far behind, drop the time we can't catch up
+                // with instead of spiraling.
+                if steps == max_steps {
+                    self.accumulated %= dt;
+                }
+
+                Ticks {
+                    steps,
+                    dt,
+                    alpha: self.accumulated / dt,
+                }
+            }
+        }
+    }
+
+    pub fn timestep(&self) -> &Timestep {
+        &self.timestep
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Accumulator, Timestep};
+
+    #[test]
+    fn fixed() {
+        let mut acc = Accumulator::new(Timestep::Fixed {
+            tick_rate: 10.,
+            max_steps: 3,
+        });
+
+        let ticks = acc.advance(0.25);
+        assert_eq!(ticks.steps, 2);
+        assert!((ticks.dt - 0.1).abs() < 1e-12);
+        assert!((ticks.alpha - 0.5).abs() < 1e-9);
+
+        let ticks = acc.advance(0.02);
+        assert_eq!(ticks.steps, 0);
+        assert!((ticks.alpha - 0.7).abs() < 1e-9);
+
+        // 1.07s behind but only 3 steps allowed.
+        let ticks = acc.advance(1.);
+        assert_eq!(ticks.steps, 3);
+        assert!(ticks.alpha < 1.);
+    }
+
+    #[test]
+    fn variable() {
+        let mut acc = Accumulator::new(Timestep::Variable);
+        let ticks = acc.advance(0.3);
+        assert_eq!((ticks.steps, ticks.dt, ticks.alpha), (1, 0.3, 1.));
+    }
+}
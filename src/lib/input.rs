@@ -0,0 +1,306 @@
+use std::{fmt::Display, path::Path};
+
+use winit::event::{MouseButton, VirtualKeyCode};
+use winit_input_helper::WinitInputHelper;
+
+use crate::resources::ResourceError;
+
+/// Generate the conversions between keys and their names (the
+/// names of the variants).
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        fn key_name(key: VirtualKeyCode) -> &'static str {
+            match key {
+                $(VirtualKeyCode::$key => stringify!($key),)*
+            }
+        }
+
+        fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+            match name {
+                $(stringify!($key) => Some(VirtualKeyCode::$key),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+key_names! {
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0, A, B, C, D, E, F, G, H, I, J, K,
+    L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9,
+    F10, F11, F12, F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24, Snapshot, Scroll,
+    Pause, Insert, Home, Delete, End, PageDown, PageUp, Left, Up, Right, Down, Back, Return,
+    Space, Compose, Caret, Numlock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5,
+    Numpad6, Numpad7, Numpad8, Numpad9, NumpadAdd, NumpadDivide, NumpadDecimal, NumpadComma,
+    NumpadEnter, NumpadEquals, NumpadMultiply, NumpadSubtract, AbntC1, AbntC2, Apostrophe, Apps,
+    Asterisk, At, Ax, Backslash, Calculator, Capital, Colon, Comma, Convert, Equals, Grave,
+    Kana, Kanji, LAlt, LBracket, LControl, LShift, LWin, Mail, MediaSelect, MediaStop, Minus,
+    Mute, MyComputer, NavigateForward, NavigateBackward, NextTrack, NoConvert, OEM102, Period,
+    PlayPause, Plus, Power, PrevTrack, RAlt, RBracket, RControl, RShift, RWin, Semicolon, Slash,
+    Sleep, Stop, Sysrq, Tab, Underline, Unlabeled, VolumeDown, VolumeUp, Wake, WebBack,
+    WebFavorites, WebForward, WebHome, WebRefresh, WebSearch, WebStop, Yen, Copy, Paste, Cut,
+}
+
+/// A key or a mouse button an action can be bound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(VirtualKeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// The index WinitInputHelper uses for a mouse button.
+    fn mouse_index(button: MouseButton) -> usize {
+        match button {
+            MouseButton::Left => 0,
+            MouseButton::Right => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Other(n) => n as usize,
+        }
+    }
+
+    pub fn pressed(&self, input: &WinitInputHelper) -> bool {
+        match *self {
+            Binding::Key(key) => input.key_pressed(key),
+            Binding::Mouse(button) => input.mouse_pressed(Binding::mouse_index(button)),
+        }
+    }
+    pub fn held(&self, input: &WinitInputHelper) -> bool {
+        match *self {
+            Binding::Key(key) => input.key_held(key),
+            Binding::Mouse(button) => input.mouse_held(Binding::mouse_index(button)),
+        }
+    }
+    pub fn released(&self, input: &WinitInputHelper) -> bool {
+        match *self {
+            Binding::Key(key) => input.key_released(key),
+            Binding::Mouse(button) => input.mouse_released(Binding::mouse_index(button)),
+        }
+    }
+
+    /// Parse a binding from its name: the name of a key
+    /// (`Space`, `A`, `Key1`, ...) or `MouseLeft`, `MouseRight`,
+    /// `MouseMiddle` or `Mouse<n>`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "MouseLeft" => Some(Binding::Mouse(MouseButton::Left)),
+            "MouseRight" => Some(Binding::Mouse(MouseButton::Right)),
+            "MouseMiddle" => Some(Binding::Mouse(MouseButton::Middle)),
+            _ => match name.strip_prefix("Mouse").map(str::parse) {
+                Some(Ok(n)) => Some(Binding::Mouse(MouseButton::Other(n))),
+                _ => key_from_name(name).map(Binding::Key),
+            },
+        }
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{}", key_name(*key)),
+            Binding::Mouse(MouseButton::Left) => write!(f, "MouseLeft"),
+            Binding::Mouse(MouseButton::Right) => write!(f, "MouseRight"),
+            Binding::Mouse(MouseButton::Middle) => write!(f, "MouseMiddle"),
+            Binding::Mouse(MouseButton::Other(n)) => write!(f, "Mouse{}", n),
+        }
+    }
+}
+
+/// Maps named actions ("jump", "move_left", ...) to the keys and
+/// mouse buttons that trigger them.
+///
+/// It can be saved to and loaded from a text file with one action
+/// per line (lines starting with `#` are comments):
+/// ```text
+/// move_left = Left, A
+/// jump = Up, W, Space
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputMap {
+    actions: Vec<(String, Vec<Binding>)>,
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        InputMap {
+            actions: Vec::new(),
+        }
+    }
+
+    /// Add `binding` to the bindings of `action`.
+    pub fn bind(&mut self, action: &str, binding: Binding) -> &mut Self {
+        match self.actions.iter_mut().find(|(name, _)| name == action) {
+            Some((_, bindings)) if bindings.contains(&binding) => (),
+            Some((_, bindings)) => bindings.push(binding),
+            None => self.actions.push((action.to_string(), vec![binding])),
+        }
+        self
+    }
+
+    /// Replace the bindings of `action`.
+    pub fn rebind(&mut self, action: &str, bindings: &[Binding]) -> &mut Self {
+        self.unbind_all(action);
+        for binding in bindings {
+            self.bind(action, *binding);
+        }
+        self
+    }
+
+    pub fn unbind(&mut self, action: &str, binding: Binding) {
+        if let Some((_, bindings)) = self.actions.iter_mut().find(|(name, _)| name == action) {
+            bindings.retain(|b| *b != binding);
+        }
+    }
+
+    pub fn unbind_all(&mut self, action: &str) {
+        self.actions.retain(|(name, _)| name != action);
+    }
+
+    /// The bindings of `action`, empty if it doesn't exist.
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions
+            .iter()
+            .find(|(name, _)| name == action)
+            .map_or(&[], |(_, bindings)| bindings)
+    }
+
+    /// The names of the actions, in the order they were added.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Return true if one of the bindings of `action` was pressed
+    /// this update.
+    pub fn pressed(&self, input: &WinitInputHelper, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.pressed(input))
+    }
+    /// Return true if one of the bindings of `action` is held.
+    pub fn held(&self, input: &WinitInputHelper, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.held(input))
+    }
+    /// Return true if one of the bindings of `action` was released
+    /// this update and none is still held.
+    pub fn released(&self, input: &WinitInputHelper, action: &str) -> bool {
+        let bindings = self.bindings(action);
+        bindings.iter().any(|b| b.released(input)) && !bindings.iter().any(|b| b.held(input))
+    }
+
+    /// Parse an InputMap from the content of a file, `path` is only
+    /// used in errors.
+    pub fn parse(path: impl AsRef<Path>, content: &str) -> Result<Self, ResourceError> {
+        let mut map = InputMap::new();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| ResourceError::Parse {
+                path: path.as_ref().to_owned(),
+                line: i + 1,
+                message,
+            };
+
+            let (action, bindings) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected `action = bindings`".to_string()))?;
+            let action = action.trim();
+            if action.is_empty() {
+                return Err(error("missing action name".to_string()));
+            }
+            for name in bindings.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                let binding = Binding::from_name(name)
+                    .ok_or_else(|| error(format!("unknown key or button {:?}", name)))?;
+                map.bind(action, binding);
+            }
+        }
+        Ok(map)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ResourceError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|source| ResourceError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        InputMap::parse(path, &content)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ResourceError> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string()).map_err(|source| ResourceError::Io {
+            path: path.to_owned(),
+            source,
+        })
+    }
+}
+
+impl Display for InputMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (action, bindings) in &self.actions {
+            write!(f, "{} =", action)?;
+            for (i, binding) in bindings.iter().enumerate() {
+                write!(f, "{}{}", if i == 0 { " " } else { ", " }, binding)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use winit::event::{MouseButton, VirtualKeyCode};
+
+    use crate::resources::ResourceError;
+
+    use super::{Binding, InputMap};
+
+    #[test]
+    fn parse_and_save() {
+        let content =
+            "# movement\nmove_left = Left, A\njump=Up,W , Space\nshoot = MouseLeft, Mouse4\n";
+        let map = InputMap::parse("input.txt", content).unwrap();
+
+        assert_eq!(
+            map.bindings("move_left"),
+            [
+                Binding::Key(VirtualKeyCode::Left),
+                Binding::Key(VirtualKeyCode::A)
+            ]
+        );
+        assert_eq!(map.bindings("jump").len(), 3);
+        assert_eq!(
+            map.bindings("shoot"),
+            [
+                Binding::Mouse(MouseButton::Left),
+                Binding::Mouse(MouseButton::Other(4))
+            ]
+        );
+        assert!(map.bindings("dance").is_empty());
+
+        let saved = map.to_string();
+        assert_eq!(
+            saved,
+            "move_left = Left, A\njump = Up, W, Space\nshoot = MouseLeft, Mouse4\n"
+        );
+        assert_eq!(InputMap::parse("input.txt", &saved).unwrap(), map);
+
+        assert!(matches!(
+            InputMap::parse("input.txt", "jump = Up\n\nfly = Wings"),
+            Err(ResourceError::Parse { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn rebind() {
+        let mut map = InputMap::new();
+        map.bind("jump", Binding::Key(VirtualKeyCode::Up))
+            .bind("jump", Binding::Key(VirtualKeyCode::Up));
+        assert_eq!(map.bindings("jump").len(), 1);
+
+        map.rebind("jump", &[Binding::Key(VirtualKeyCode::Space)]);
+        assert_eq!(map.bindings("jump"), [Binding::Key(VirtualKeyCode::Space)]);
+
+        map.unbind("jump", Binding::Key(VirtualKeyCode::Space));
+        assert!(map.bindings("jump").is_empty());
+    }
+}
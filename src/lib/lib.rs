@@ -1,9 +1,17 @@
+pub mod assets;
+pub mod camera;
+pub mod collision;
 pub mod color;
+pub mod controller;
+pub mod draw;
 pub mod drawable;
+pub mod font;
 pub mod game;
+pub mod input;
 pub mod mat;
 pub mod object;
 pub mod physics;
 pub mod resources;
+pub mod snapshot;
 pub mod uv_map;
 pub mod vec2;
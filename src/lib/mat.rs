@@ -5,16 +5,25 @@ use std::{
 
 mod enumerate;
 mod mat_slice;
+mod mat_slice_mut;
+mod orientation;
+mod scaled_mat;
 mod sliced_mat;
-
-pub use {mat_slice::MatSlice, sliced_mat::SlicedMat};
+mod sliced_mat_mut;
+
+pub use {
+    mat_slice::{MatSlice, PlainSlice},
+    mat_slice_mut::MatSliceMut,
+    orientation::Orientation,
+    scaled_mat::ScaledMat,
+    sliced_mat::SlicedMat,
+    sliced_mat_mut::SlicedMatMut,
+};
 
 fn dims_product(dims: (usize, usize)) -> usize {
     dims.0
         .checked_mul(dims.1)
         .expect("Mat dimensions are too big so their product is out of bounds")
-        .try_into()
-        .unwrap()
 }
 
 fn get_vec_index(index: (usize, usize), width: usize) -> usize {
@@ -90,19 +99,13 @@ impl<T> Mat<T> {
         self.vec.swap(a, b);
     }
 
-    /// Transpose the Mat.
+    /// Transpose the Mat, see `MatSlice::transposed` for a view
+    /// that doesn't copy the Mat.
     pub fn transpose(&mut self)
     where
         T: Clone,
     {
-        let old = self.vec.to_owned();
-        for x in 0..self.dims.0 {
-            for y in 0..self.dims.1 {
-                let index = self.get_vec_index((x, y));
-                let swapped_index = get_vec_index((y, x), self.dims.1);
-                self.vec[swapped_index] = old[index].to_owned();
-            }
-        }
+        self.vec = self.transposed().to_vec();
         self.invert_dims();
     }
 
@@ -145,6 +148,13 @@ impl<T> Mat<T> {
         &self.dims
     }
 
+    /// The items of row `y`, straight from the Vec representation
+    /// of the Mat.
+    pub fn row(&self, y: usize) -> &[T] {
+        let start = get_vec_index((0, y), self.dims.0);
+        &self.vec[start..start + self.dims.0]
+    }
+
     /// Get the position of the given Mat index in the Vec
     /// representation of the Mat.
     pub fn get_vec_index(&self, index: (usize, usize)) -> usize {
@@ -159,14 +169,22 @@ impl<T> MatSlice<T> for Mat<T> {
     fn slice_dims(&self) -> &(usize, usize) {
         &self.dims
     }
-    fn flip(&self) -> &(bool, bool) {
-        &(false, false)
+    fn orientation(&self) -> &Orientation {
+        &Orientation::IDENTITY
     }
     fn mat(&self) -> &Mat<T> {
         self
     }
 }
 
+impl<T> PlainSlice<T> for Mat<T> {}
+
+impl<T> MatSliceMut<T> for Mat<T> {
+    fn mat_mut(&mut self) -> &mut Mat<T> {
+        self
+    }
+}
+
 impl<T> Index<(usize, usize)> for Mat<T> {
     type Output = T;
 
@@ -202,9 +220,10 @@ where
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
 
-    use crate::mat::MatSlice;
+    use crate::mat::{MatSlice, MatSliceMut, Orientation, SlicedMatMut};
 
     use super::Mat;
 
@@ -218,6 +237,7 @@ mod test {
         assert_eq!(mat[(0, 1)], 3);
         assert_eq!(mat[(1, 1)], 4);
         assert_eq!(mat[(2, 1)], 5);
+        assert_eq!(mat.row(1), [3, 4, 5]);
     }
 
     #[test]
@@ -380,4 +400,167 @@ mod test {
         assert_eq!(mat.slice((1, 1), (2, 2), (true, true))[(0, 0)], 10);
         assert_eq!(mat.slice((1, 1), (2, 2), (true, true))[(1, 1)], 5);
     }
+
+    #[test]
+    fn slice_mut() {
+        let mut mat = Mat::from_vec((0..16).collect::<Vec<_>>(), (4, 4));
+
+        mat.slice_mut((1, 1), (2, 2), (false, false)).fill(0);
+        assert_eq!(
+            mat,
+            Mat::from_vec(
+                [0, 1, 2, 3, 4, 0, 0, 7, 8, 0, 0, 11, 12, 13, 14, 15],
+                (4, 4)
+            )
+        );
+
+        let mut slice = mat.slice_mut((0, 0), (3, 1), (true, false));
+        slice.fill_with_r(|(x, _)| 20 + x);
+        slice.map_in_place(|v| v * 2);
+        assert_eq!(
+            mat.slice((0, 0), (4, 1), (false, false)).to_vec(),
+            [44, 42, 40, 3]
+        );
+
+        let source = Mat::from_vec([1, 2, 3, 4], (2, 2));
+        mat.slice_mut((2, 2), (2, 2), (true, true))
+            .copy_from(&source.as_slice());
+        assert_eq!(
+            mat.slice((2, 2), (2, 2), (false, false)).to_mat(),
+            Mat::from_vec([4, 3, 2, 1], (2, 2))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_mut_fail() {
+        let mut mat = Mat::from_vec((0..16).collect::<Vec<_>>(), (4, 4));
+        mat.slice_mut((3, 0), (2, 1), (false, false));
+    }
+
+    #[test]
+    fn rotate() {
+        let mat = Mat::from_vec((0..6).collect::<Vec<_>>(), (3, 2));
+
+        let rotated = mat.rotate_90();
+        assert_eq!(*rotated.slice_dims(), (2, 3));
+        assert_eq!(rotated.to_mat(), Mat::from_vec([3, 0, 4, 1, 5, 2], (2, 3)));
+        assert_eq!(
+            mat.rotate_180().to_mat(),
+            Mat::from_vec([5, 4, 3, 2, 1, 0], (3, 2))
+        );
+        assert_eq!(
+            mat.rotate_270().to_mat(),
+            Mat::from_vec([2, 5, 1, 4, 0, 3], (2, 3))
+        );
+        assert_eq!(
+            mat.transposed().to_mat(),
+            Mat::from_vec([0, 3, 1, 4, 2, 5], (2, 3))
+        );
+
+        assert_eq!(*mat.flip(), (false, false));
+        assert_eq!(*mat.rotate_180().flip(), (true, true));
+        assert_eq!(
+            *mat.slice_flip((true, false)).transposed().flip(),
+            (true, false)
+        );
+
+        assert_eq!(rotated.rotate_90().to_mat(), mat.rotate_180().to_mat());
+        assert_eq!(rotated.rotate_270().to_mat(), mat);
+        assert_eq!(
+            mat.slice_flip((true, false)).rotate_90().to_mat(),
+            Mat::from_vec([5, 2, 4, 1, 3, 0], (2, 3))
+        );
+
+        let slice = Mat::from_vec((0..16).collect::<Vec<_>>(), (4, 4))
+            .slice((1, 1), (3, 2), (false, false))
+            .to_mat();
+        assert_eq!(
+            slice.rotate_90().to_mat(),
+            Mat::from_vec([9, 5, 10, 6, 11, 7], (2, 3))
+        );
+    }
+
+    #[test]
+    fn slice_rotated() {
+        let mut mat = Mat::from_vec((0..6).collect::<Vec<_>>(), (3, 2));
+
+        let rotated = mat.rotate_90();
+        assert_eq!(
+            rotated.as_slice().to_mat(),
+            Mat::from_vec([3, 0, 4, 1, 5, 2], (2, 3))
+        );
+        // Flipped in the axes of the rotated view.
+        assert_eq!(
+            rotated.slice_flip((true, false)).to_mat(),
+            Mat::from_vec([0, 3, 1, 4, 2, 5], (2, 3))
+        );
+        assert_eq!(
+            rotated.slice_flip((false, true)).to_mat(),
+            Mat::from_vec([5, 2, 4, 1, 3, 0], (2, 3))
+        );
+        assert_eq!(
+            mat.rotate_90().rotate_90().as_slice().to_mat(),
+            mat.rotate_180().to_mat()
+        );
+
+        let mut rotated =
+            SlicedMatMut::oriented(&mut mat, (0, 0), (3, 2), Orientation::IDENTITY.rotate_90());
+        rotated.slice_flip_mut((true, false))[(0, 0)] = 9;
+        assert_eq!(mat, Mat::from_vec([9, 1, 2, 3, 4, 5], (3, 2)));
+    }
+
+    #[test]
+    fn scale() {
+        let mat = Mat::from_vec([0, 1, 2, 3], (2, 2));
+
+        let scaled = mat.scale(2);
+        assert_eq!(*scaled.slice_dims(), (4, 4));
+        assert_eq!(
+            scaled.to_mat(),
+            Mat::from_vec([0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 3, 3, 2, 2, 3, 3], (4, 4))
+        );
+        assert_eq!(
+            mat.rotate_90().scale(2).to_mat(),
+            mat.scale(2).to_mat().rotate_90().to_mat()
+        );
+    }
+
+    #[test]
+    fn scale_then_slice() {
+        let mat = Mat::from_vec((0..6).collect::<Vec<_>>(), (3, 2));
+        let scaled = mat.scale(2);
+
+        assert_eq!(*scaled.rotate_90().slice_dims(), (4, 6));
+        assert_eq!(
+            scaled.rotate_90().to_mat(),
+            scaled.to_mat().rotate_90().to_mat()
+        );
+        assert_eq!(scaled.as_slice().to_mat(), scaled.to_mat());
+        assert_eq!(
+            scaled.slice((1, 1), (4, 2), (true, false)).to_mat(),
+            scaled
+                .to_mat()
+                .slice((1, 1), (4, 2), (true, false))
+                .to_mat()
+        );
+        assert_eq!(scaled.scale(2).to_mat(), mat.scale(4).to_mat());
+        assert_eq!(
+            scaled.rotate_90().scale(3).to_mat(),
+            mat.rotate_90().scale(6).to_mat()
+        );
+    }
+
+    #[test]
+    fn resize() {
+        let mat = Mat::from_vec((0..6).collect::<Vec<_>>(), (3, 2));
+
+        assert_eq!(mat.resize((3, 2)), mat);
+        assert_eq!(mat.resize((6, 4)), mat.scale(2).to_mat());
+        assert_eq!(mat.resize((2, 1)), Mat::from_vec([3, 5], (2, 1)));
+        assert_eq!(
+            mat.slice_flip((true, false)).resize((3, 1)),
+            Mat::from_vec([5, 4, 3], (3, 1))
+        );
+    }
 }
@@ -1,6 +1,19 @@
 use std::ops::Index;
 
-use super::{dims_product, enumerate::EnumerateMat, get_vec_index, Mat, SlicedMat};
+use super::{dims_product, enumerate::EnumerateMat, Mat, Orientation, ScaledMat, SlicedMat};
+
+/// Panic if the slice is not entirely inside a Mat with
+/// dimensions `dims`.
+pub(super) fn check_slice(
+    dims: (usize, usize),
+    slice_index: (usize, usize),
+    slice_dims: (usize, usize),
+) {
+    assert!(slice_index.0 < dims.0);
+    assert!(slice_index.1 < dims.1);
+    assert!(slice_index.0 + slice_dims.0 - 1 < dims.0);
+    assert!(slice_index.1 + slice_dims.1 - 1 < dims.1);
+}
 
 pub trait MatSlice<T>: Index<(usize, usize), Output = T> {
     /// Create a new Vec representation of a Mat from the
@@ -9,14 +22,9 @@ pub trait MatSlice<T>: Index<(usize, usize), Output = T> {
     where
         T: Clone,
     {
-        let mut new_vec = self.mat().vec().clone();
-        new_vec.truncate(self.len());
-
-        // TODO rework that
-        for (x, y) in EnumerateMat::<true>::new(*self.slice_dims()) {
-            new_vec[get_vec_index((x, y), self.slice_dims().0)] = self[(x, y)].to_owned();
-        }
-        new_vec
+        self.enumerate_r()
+            .map(|index| self[index].to_owned())
+            .collect()
     }
 
     /// Return the 2d Vec representation of the Mat.
@@ -46,26 +54,118 @@ pub trait MatSlice<T>: Index<(usize, usize), Output = T> {
         slice_index: (usize, usize),
         slice_dims: (usize, usize),
         flip_slice: (bool, bool),
-    ) -> SlicedMat<'a, T> {
+    ) -> SlicedMat<'a, T>
+    where
+        Self: PlainSlice<T>,
+    {
+        let factor = self.factor();
         let dims = self.mat().dims();
-        assert!(slice_index.0 < dims.0);
-        assert!(slice_index.1 < dims.1);
-        assert!(slice_index.0 + slice_dims.0 - 1 < dims.0);
-        assert!(slice_index.1 + slice_dims.1 - 1 < dims.1);
+        check_slice((dims.0 * factor, dims.1 * factor), slice_index, slice_dims);
 
-        SlicedMat::new(self.mat(), slice_index, slice_dims, flip_slice)
+        SlicedMat::scaled(
+            self.mat(),
+            slice_index,
+            slice_dims,
+            flip_slice.into(),
+            factor,
+        )
     }
 
-    /// Create a SlicedMat from the entire Mat that can be flipped.
-    fn slice_flip(&self, flip_slice: (bool, bool)) -> SlicedMat<T> {
-        self.slice((0, 0), *self.slice_dims(), flip_slice)
+    /// Create a SlicedMat of the entire MatSlice that can be
+    /// flipped, in the axes of the MatSlice.
+    fn slice_flip(&self, flip_slice: (bool, bool)) -> SlicedMat<'_, T>
+    where
+        Self: PlainSlice<T>,
+    {
+        self.reorient(self.orientation().flipped(flip_slice))
     }
 
-    /// Create a SlicedMat from the entire Mat.
-    fn as_slice(&self) -> SlicedMat<T> {
+    /// Create a SlicedMat of the entire MatSlice.
+    fn as_slice(&self) -> SlicedMat<'_, T>
+    where
+        Self: PlainSlice<T>,
+    {
         self.slice_flip((false, false))
     }
 
+    /// Create a SlicedMat of the same area as the MatSlice but
+    /// with another orientation.
+    fn reorient(&self, orientation: Orientation) -> SlicedMat<'_, T>
+    where
+        Self: PlainSlice<T>,
+    {
+        SlicedMat::scaled(
+            self.mat(),
+            *self.slice_index(),
+            self.orientation().view_dims(*self.slice_dims()),
+            orientation,
+            self.factor(),
+        )
+    }
+
+    /// View of the MatSlice rotated 90° clockwise.
+    fn rotate_90(&self) -> SlicedMat<'_, T>
+    where
+        Self: PlainSlice<T>,
+    {
+        self.reorient(self.orientation().rotate_90())
+    }
+
+    /// View of the MatSlice rotated 180°.
+    fn rotate_180(&self) -> SlicedMat<'_, T>
+    where
+        Self: PlainSlice<T>,
+    {
+        self.reorient(self.orientation().rotate_180())
+    }
+
+    /// View of the MatSlice rotated 270° clockwise.
+    fn rotate_270(&self) -> SlicedMat<'_, T>
+    where
+        Self: PlainSlice<T>,
+    {
+        self.reorient(self.orientation().rotate_270())
+    }
+
+    /// View of the MatSlice with its axes swapped.
+    fn transposed(&self) -> SlicedMat<'_, T>
+    where
+        Self: PlainSlice<T>,
+    {
+        self.reorient(self.orientation().transpose())
+    }
+
+    /// View of the MatSlice scaled up `factor` times.
+    fn scale(&self, factor: usize) -> ScaledMat<'_, T, Self>
+    where
+        Self: Sized + PlainSlice<T>,
+    {
+        ScaledMat::new(self, factor)
+    }
+
+    /// Create a new Mat with dimensions `dims` from the MatSlice
+    /// using nearest-neighbour sampling.
+    fn resize(&self, dims: (usize, usize)) -> Mat<T>
+    where
+        T: Clone,
+    {
+        let slice_dims = *self.slice_dims();
+        // Sample at the center of the destination pixels.
+        let source = |i: usize, from: usize, to: usize| (2 * i + 1) * from / (2 * to);
+
+        let mut vec = Vec::with_capacity(dims.0 * dims.1);
+        for (x, y) in EnumerateMat::<true>::new(dims) {
+            vec.push(
+                self[(
+                    source(x, slice_dims.0, dims.0),
+                    source(y, slice_dims.1, dims.1),
+                )]
+                    .to_owned(),
+            );
+        }
+        Mat::from_vec(vec, dims)
+    }
+
     /// Enumerate Mat indexes row-wise.
     fn enumerate_r(&self) -> EnumerateMat<true> {
         EnumerateMat::new(*self.slice_dims())
@@ -85,13 +185,32 @@ pub trait MatSlice<T>: Index<(usize, usize), Output = T> {
     fn len(&self) -> usize {
         dims_product(*self.slice_dims())
     }
+    /// Check if the MatSlice has no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
     /// Get the start index of the MatSlice.
     fn slice_index(&self) -> &(usize, usize);
     /// Get the dimensions of the MatSlice.
     fn slice_dims(&self) -> &(usize, usize);
-    /// Get the flip "state" of the MatSlice.
-    fn flip(&self) -> &(bool, bool);
+    /// Get the flip "state" of the MatSlice, in the axes of the
+    /// Mat (see `MatSlice::orientation`).
+    fn flip(&self) -> &(bool, bool) {
+        &self.orientation().flip
+    }
+    /// Get the orientation of the MatSlice.
+    fn orientation(&self) -> &Orientation;
+    /// Get how many times each item of the Mat is repeated in both
+    /// directions, the start index and the dimensions are those of
+    /// the scaled up Mat.
+    fn factor(&self) -> usize {
+        1
+    }
     /// Get a reference to the Mat referenced by this MatSlice.
     fn mat(&self) -> &Mat<T>;
 }
+
+/// A MatSlice whose items are the items of `MatSlice::mat`, so its
+/// views can be built from the Mat and its position in it.
+pub trait PlainSlice<T>: MatSlice<T> {}
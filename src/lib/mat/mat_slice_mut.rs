@@ -0,0 +1,93 @@
+use std::ops::IndexMut;
+
+use super::{mat_slice::check_slice, Mat, MatSlice, SlicedMatMut};
+
+pub trait MatSliceMut<T>: MatSlice<T> + IndexMut<(usize, usize), Output = T> {
+    /// Fill the MatSlice with the given value.
+    fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        for index in self.enumerate_r() {
+            self[index] = value.to_owned();
+        }
+    }
+
+    /// Fill the MatSlice row-wise with the value returned by the
+    /// provided closure.
+    fn fill_with_r<F>(&mut self, mut f: F)
+    where
+        F: FnMut((usize, usize)) -> T,
+    {
+        for index in self.enumerate_r() {
+            self[index] = f(index);
+        }
+    }
+
+    /// Fill the MatSlice column-wise with the value returned by the
+    /// provided closure.
+    fn fill_with_c<F>(&mut self, mut f: F)
+    where
+        F: FnMut((usize, usize)) -> T,
+    {
+        for index in self.enumerate_c() {
+            self[index] = f(index);
+        }
+    }
+
+    /// Copy the items of `other` into the MatSlice, both must have
+    /// the same dimensions.
+    fn copy_from(&mut self, other: &impl MatSlice<T>)
+    where
+        T: Clone,
+    {
+        assert_eq!(self.slice_dims(), other.slice_dims());
+
+        for index in self.enumerate_r() {
+            self[index] = other[index].to_owned();
+        }
+    }
+
+    /// Replace every item of the MatSlice with the value returned
+    /// by the provided closure.
+    fn map_in_place<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> T,
+    {
+        for index in self.enumerate_r() {
+            self[index] = f(&self[index]);
+        }
+    }
+
+    /// Create a mutable MatSlice starting at `index` with width and
+    /// height `dims`. The selected area can be flipped row-wise with
+    /// `flip.0` and/or column-wise with `flip.1`.
+    fn slice_mut(
+        &mut self,
+        slice_index: (usize, usize),
+        slice_dims: (usize, usize),
+        flip_slice: (bool, bool),
+    ) -> SlicedMatMut<'_, T> {
+        check_slice(*self.mat().dims(), slice_index, slice_dims);
+
+        SlicedMatMut::new(self.mat_mut(), slice_index, slice_dims, flip_slice)
+    }
+
+    /// Create a mutable SlicedMat of the entire MatSlice that can
+    /// be flipped, in the axes of the MatSlice.
+    fn slice_flip_mut(&mut self, flip_slice: (bool, bool)) -> SlicedMatMut<'_, T> {
+        let slice_index = *self.slice_index();
+        let area_dims = self.orientation().view_dims(*self.slice_dims());
+        let orientation = self.orientation().flipped(flip_slice);
+        SlicedMatMut::oriented(self.mat_mut(), slice_index, area_dims, orientation)
+    }
+
+    /// Create a mutable SlicedMat of the entire MatSlice.
+    fn as_slice_mut(&mut self) -> SlicedMatMut<'_, T> {
+        self.slice_flip_mut((false, false))
+    }
+
+    /// Get a mutable reference to the Mat referenced by this
+    /// MatSlice.
+    fn mat_mut(&mut self) -> &mut Mat<T>;
+}
@@ -0,0 +1,110 @@
+/// One of the eight orientations of a rectangle (rotations and
+/// mirror images): the axes are optionally swapped then the
+/// result is optionally flipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Orientation {
+    /// Swap the x and y axes.
+    pub transpose: bool,
+    /// Flip row-wise (`flip.0`) and/or column-wise (`flip.1`), in
+    /// the axes of the source.
+    pub flip: (bool, bool),
+}
+
+impl Orientation {
+    pub const IDENTITY: Orientation = Orientation::from_flip((false, false));
+
+    pub const fn from_flip(flip: (bool, bool)) -> Self {
+        Orientation {
+            transpose: false,
+            flip,
+        }
+    }
+
+    /// Swap the axes of the view.
+    pub const fn transpose(self) -> Self {
+        Orientation {
+            transpose: !self.transpose,
+            flip: self.flip,
+        }
+    }
+
+    /// Flip the view row-wise.
+    pub const fn flip_r(self) -> Self {
+        let (r, c) = self.flip;
+        Orientation {
+            transpose: self.transpose,
+            flip: if self.transpose { (r, !c) } else { (!r, c) },
+        }
+    }
+
+    /// Flip the view column-wise.
+    pub const fn flip_c(self) -> Self {
+        let (r, c) = self.flip;
+        Orientation {
+            transpose: self.transpose,
+            flip: if self.transpose { (!r, c) } else { (r, !c) },
+        }
+    }
+
+    /// Flip the view row-wise if `flip.0` and column-wise if
+    /// `flip.1`.
+    pub const fn flipped(self, flip: (bool, bool)) -> Self {
+        let mut orientation = self;
+        if flip.0 {
+            orientation = orientation.flip_r();
+        }
+        if flip.1 {
+            orientation = orientation.flip_c();
+        }
+        orientation
+    }
+
+    /// Rotate the view 90° clockwise.
+    pub const fn rotate_90(self) -> Self {
+        self.transpose().flip_r()
+    }
+
+    /// Rotate the view 180°.
+    pub const fn rotate_180(self) -> Self {
+        self.flip_r().flip_c()
+    }
+
+    /// Rotate the view 270° clockwise (90° counterclockwise).
+    pub const fn rotate_270(self) -> Self {
+        self.transpose().flip_c()
+    }
+
+    /// The dimensions of the view of an area with dimensions
+    /// `dims`.
+    pub const fn view_dims(&self, dims: (usize, usize)) -> (usize, usize) {
+        if self.transpose {
+            (dims.1, dims.0)
+        } else {
+            dims
+        }
+    }
+
+    /// The index in an area with dimensions `dims` of the item at
+    /// `index` in the view.
+    pub const fn source_index(
+        &self,
+        dims: (usize, usize),
+        index: (usize, usize),
+    ) -> (usize, usize) {
+        let (x, y) = if self.transpose {
+            (index.1, index.0)
+        } else {
+            index
+        };
+        (
+            if self.flip.0 { dims.0 - 1 - x } else { x },
+            if self.flip.1 { dims.1 - 1 - y } else { y },
+        )
+    }
+}
+
+impl From<(bool, bool)> for Orientation {
+    fn from(value: (bool, bool)) -> Self {
+        Orientation::from_flip(value)
+    }
+}
@@ -0,0 +1,69 @@
+use std::{marker::PhantomData, ops::Index};
+
+use super::{Mat, MatSlice, Orientation, PlainSlice};
+
+/// View of a MatSlice scaled up by an integer factor, each item is
+/// repeated `factor` times in both directions.
+///
+/// Slicing and orienting a ScaledMat gives views of the scaled up
+/// items, which is why it only scales a PlainSlice.
+pub struct ScaledMat<'a, T, S>
+where
+    S: PlainSlice<T>,
+{
+    slice: &'a S,
+    factor: usize,
+    slice_index: (usize, usize),
+    slice_dims: (usize, usize),
+    _item: PhantomData<T>,
+}
+
+impl<'a, T, S> ScaledMat<'a, T, S>
+where
+    S: PlainSlice<T>,
+{
+    pub fn new(slice: &'a S, factor: usize) -> Self {
+        let (index, dims) = (slice.slice_index(), slice.slice_dims());
+        ScaledMat {
+            slice,
+            factor,
+            slice_index: (index.0 * factor, index.1 * factor),
+            slice_dims: (dims.0 * factor, dims.1 * factor),
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, S> MatSlice<T> for ScaledMat<'a, T, S>
+where
+    S: PlainSlice<T>,
+{
+    fn slice_index(&self) -> &(usize, usize) {
+        &self.slice_index
+    }
+    fn slice_dims(&self) -> &(usize, usize) {
+        &self.slice_dims
+    }
+    fn orientation(&self) -> &Orientation {
+        self.slice.orientation()
+    }
+    fn factor(&self) -> usize {
+        self.slice.factor() * self.factor
+    }
+    fn mat(&self) -> &Mat<T> {
+        self.slice.mat()
+    }
+}
+
+impl<'a, T, S> PlainSlice<T> for ScaledMat<'a, T, S> where S: PlainSlice<T> {}
+
+impl<'a, T, S> Index<(usize, usize)> for ScaledMat<'a, T, S>
+where
+    S: PlainSlice<T>,
+{
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.slice[(index.0 / self.factor, index.1 / self.factor)]
+    }
+}
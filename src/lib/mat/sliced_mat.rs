@@ -1,13 +1,29 @@
 use std::ops::Index;
 
-use super::{Mat, MatSlice};
+use super::{Mat, MatSlice, Orientation, PlainSlice};
+
+/// Get the index in the Mat of the item at `index` in a slice
+/// with (oriented) dimensions `slice_dims`.
+pub(super) fn mat_index(
+    slice_index: (usize, usize),
+    slice_dims: (usize, usize),
+    orientation: Orientation,
+    index: (usize, usize),
+) -> (usize, usize) {
+    // Swapping the axes twice gives back the dimensions of the
+    // area in the Mat.
+    let area_dims = orientation.view_dims(slice_dims);
+    let index = orientation.source_index(area_dims, index);
+    (slice_index.0 + index.0, slice_index.1 + index.1)
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct SlicedMat<'a, T> {
     mat: &'a Mat<T>,
     slice_index: (usize, usize),
     slice_dims: (usize, usize),
-    flip_slice: (bool, bool),
+    orientation: Orientation,
+    factor: usize,
 }
 
 impl<'a, T> SlicedMat<'a, T> {
@@ -16,14 +32,64 @@ impl<'a, T> SlicedMat<'a, T> {
         slice_index: (usize, usize),
         slice_dims: (usize, usize),
         flip_slice: (bool, bool),
+    ) -> Self {
+        SlicedMat::oriented(mat, slice_index, slice_dims, flip_slice.into())
+    }
+
+    /// Create a SlicedMat of the area starting at `slice_index`
+    /// with dimensions `area_dims` in the Mat, seen with the given
+    /// orientation.
+    pub fn oriented(
+        mat: &'a Mat<T>,
+        slice_index: (usize, usize),
+        area_dims: (usize, usize),
+        orientation: Orientation,
+    ) -> Self {
+        SlicedMat::scaled(mat, slice_index, area_dims, orientation, 1)
+    }
+
+    /// Same as `SlicedMat::oriented` but in the Mat scaled up
+    /// `factor` times, `slice_index` and `area_dims` included.
+    pub fn scaled(
+        mat: &'a Mat<T>,
+        slice_index: (usize, usize),
+        area_dims: (usize, usize),
+        orientation: Orientation,
+        factor: usize,
     ) -> Self {
         SlicedMat {
             mat,
             slice_index,
-            slice_dims,
-            flip_slice,
+            slice_dims: orientation.view_dims(area_dims),
+            orientation,
+            factor,
         }
     }
+
+    /// Same as `MatSlice::reorient` but the new SlicedMat borrows
+    /// the Mat for as long as this one.
+    pub fn reorient(&self, orientation: Orientation) -> SlicedMat<'a, T> {
+        SlicedMat::scaled(
+            self.mat,
+            self.slice_index,
+            self.orientation.view_dims(self.slice_dims),
+            orientation,
+            self.factor,
+        )
+    }
+
+    /// Same as `MatSlice::scale` but gives a SlicedMat that borrows
+    /// the Mat for as long as this one.
+    pub fn scale_up(&self, factor: usize) -> SlicedMat<'a, T> {
+        SlicedMat::scaled(
+            self.mat,
+            (self.slice_index.0 * factor, self.slice_index.1 * factor),
+            self.orientation
+                .view_dims((self.slice_dims.0 * factor, self.slice_dims.1 * factor)),
+            self.orientation,
+            self.factor * factor,
+        )
+    }
 }
 
 impl<'a, T> MatSlice<T> for SlicedMat<'a, T> {
@@ -33,29 +99,23 @@ impl<'a, T> MatSlice<T> for SlicedMat<'a, T> {
     fn slice_dims(&self) -> &(usize, usize) {
         &self.slice_dims
     }
-    fn flip(&self) -> &(bool, bool) {
-        &self.flip_slice
+    fn orientation(&self) -> &Orientation {
+        &self.orientation
+    }
+    fn factor(&self) -> usize {
+        self.factor
     }
     fn mat(&self) -> &Mat<T> {
         self.mat
     }
 }
 
+impl<'a, T> PlainSlice<T> for SlicedMat<'a, T> {}
+
 impl<'a, T> Index<(usize, usize)> for SlicedMat<'a, T> {
     type Output = T;
     fn index(&self, index: (usize, usize)) -> &Self::Output {
-        let index = (
-            if self.flip_slice.0 {
-                self.slice_index.0 + self.slice_dims.0 - 1 - index.0
-            } else {
-                self.slice_index.0 + index.0
-            },
-            if self.flip_slice.1 {
-                self.slice_index.1 + self.slice_dims.1 - 1 - index.1
-            } else {
-                self.slice_index.1 + index.1
-            },
-        );
-        &self.mat[index]
+        let (x, y) = mat_index(self.slice_index, self.slice_dims, self.orientation, index);
+        &self.mat[(x / self.factor, y / self.factor)]
     }
 }
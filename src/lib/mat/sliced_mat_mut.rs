@@ -0,0 +1,75 @@
+use std::ops::{Index, IndexMut};
+
+use super::{sliced_mat::mat_index, Mat, MatSlice, MatSliceMut, Orientation, PlainSlice};
+
+#[derive(Debug)]
+pub struct SlicedMatMut<'a, T> {
+    mat: &'a mut Mat<T>,
+    slice_index: (usize, usize),
+    slice_dims: (usize, usize),
+    orientation: Orientation,
+}
+
+impl<'a, T> SlicedMatMut<'a, T> {
+    pub fn new(
+        mat: &'a mut Mat<T>,
+        slice_index: (usize, usize),
+        slice_dims: (usize, usize),
+        flip_slice: (bool, bool),
+    ) -> Self {
+        SlicedMatMut::oriented(mat, slice_index, slice_dims, flip_slice.into())
+    }
+
+    /// Create a SlicedMatMut of the area starting at `slice_index`
+    /// with dimensions `area_dims` in the Mat, seen with the given
+    /// orientation.
+    pub fn oriented(
+        mat: &'a mut Mat<T>,
+        slice_index: (usize, usize),
+        area_dims: (usize, usize),
+        orientation: Orientation,
+    ) -> Self {
+        SlicedMatMut {
+            mat,
+            slice_index,
+            slice_dims: orientation.view_dims(area_dims),
+            orientation,
+        }
+    }
+}
+
+impl<'a, T> MatSlice<T> for SlicedMatMut<'a, T> {
+    fn slice_index(&self) -> &(usize, usize) {
+        &self.slice_index
+    }
+    fn slice_dims(&self) -> &(usize, usize) {
+        &self.slice_dims
+    }
+    fn orientation(&self) -> &Orientation {
+        &self.orientation
+    }
+    fn mat(&self) -> &Mat<T> {
+        self.mat
+    }
+}
+
+impl<'a, T> PlainSlice<T> for SlicedMatMut<'a, T> {}
+
+impl<'a, T> MatSliceMut<T> for SlicedMatMut<'a, T> {
+    fn mat_mut(&mut self) -> &mut Mat<T> {
+        self.mat
+    }
+}
+
+impl<'a, T> Index<(usize, usize)> for SlicedMatMut<'a, T> {
+    type Output = T;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.mat[mat_index(self.slice_index, self.slice_dims, self.orientation, index)]
+    }
+}
+
+impl<'a, T> IndexMut<(usize, usize)> for SlicedMatMut<'a, T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.mat[mat_index(self.slice_index, self.slice_dims, self.orientation, index)]
+    }
+}
@@ -1,5 +1,28 @@
+mod world;
+
+pub use world::{Body, BodyId, BodyKind, Contact, ContactEvent, PhysicsWorld};
+
 use crate::vec2::Vec2;
 
+/// How `Physics::update` advances the position and the velocity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Integrator {
+    /// Explicit Euler for the velocity, then the kinematic formula
+    /// with the new velocity. It overshoots by `a * dt^2` every
+    /// update, kept as the default for the existing games.
+    #[default]
+    Kinematic,
+    /// The new velocity moves the position. Cheap and stable, but
+    /// lags behind by `a * dt^2 / 2` every update.
+    SemiImplicitEuler,
+    /// Exact with constant forces and good at conserving energy.
+    VelocityVerlet,
+    /// Runge-Kutta 4, the most accurate when the forces depend on
+    /// the velocity, four times as costly.
+    Rk4,
+}
+
+#[derive(Debug, Clone)]
 pub struct Physics {
     m: f64, // mass in mu (mass unit)
     g: f64, // gravity strength in fu/mu
@@ -9,6 +32,25 @@ pub struct Physics {
     a: Vec2,   // acceleration in px/s^2
 
     tf: Vec2, // total force in fu (force unit)
+    /// Forces only applied during the next update.
+    once: Vec2,
+    /// Instant velocity changes applied by the next update, in
+    /// fu.s.
+    impulse: Vec2,
+
+    /// Drag proportional to the speed, in fu/(px/s).
+    linear_drag: f64,
+    /// Drag proportional to the square of the speed, in
+    /// fu/(px/s)^2.
+    quadratic_drag: f64,
+    /// Friction coefficient slowing down horizontal movement while
+    /// grounded.
+    ground_friction: f64,
+    grounded: bool,
+    /// The highest speed along each axis, in px/s.
+    max_speed: Vec2,
+
+    integrator: Integrator,
 }
 
 impl Physics {
@@ -20,21 +62,100 @@ impl Physics {
             tf: Vec2::ZERO,
             m,
             g,
+            once: Vec2::ZERO,
+            impulse: Vec2::ZERO,
+            linear_drag: 0.,
+            quadratic_drag: 0.,
+            ground_friction: 0.,
+            grounded: false,
+            max_speed: Vec2::filled_with(f64::INFINITY),
+            integrator: Integrator::default(),
         }
     }
 
-    /// Update positon, velocity and acceleration.
+    /// The acceleration of the object moving at `v`.
+    fn acceleration(&self, v: Vec2) -> Vec2 {
+        let drag = v * -(self.linear_drag + self.quadratic_drag * v.length());
+        (self.tf + self.once + drag) / self.m // Newton's second law
+    }
+
+    /// Update positon, velocity and acceleration. The impulses and
+    /// the one-shot forces are applied then cleared.
     pub fn update(&mut self, dt: f64) {
-        self.a = self.tf / self.m; // Newton's second law
+        self.v += self.impulse / self.m;
+        self.impulse = Vec2::ZERO;
 
-        self.v += self.a * dt;
-        self.pos += self.v * dt + (self.a * dt.powi(2)) / 2.;
+        if self.grounded {
+            // Kinetic friction, the normal force being the weight.
+            let slowdown = self.ground_friction * self.g * dt;
+            self.v.0 = self.v.0.signum() * (self.v.0.abs() - slowdown).max(0.);
+        }
+
+        // Before integrating too, or a big impulse would move the
+        // body further than the max speed allows.
+        self.clamp_v();
+        self.integrate(dt);
+        self.once = Vec2::ZERO;
+        self.clamp_v();
+    }
+
+    fn clamp_v(&mut self) {
+        self.v = Vec2(
+            self.v.0.clamp(-self.max_speed.0, self.max_speed.0),
+            self.v.1.clamp(-self.max_speed.1, self.max_speed.1),
+        );
+    }
+
+    fn integrate(&mut self, dt: f64) {
+        match self.integrator {
+            Integrator::Kinematic => {
+                self.a = self.acceleration(self.v);
+                self.v += self.a * dt;
+                self.pos += self.v * dt + (self.a * dt.powi(2)) / 2.;
+            }
+            Integrator::SemiImplicitEuler => {
+                self.a = self.acceleration(self.v);
+                self.v += self.a * dt;
+                self.pos += self.v * dt;
+            }
+            Integrator::VelocityVerlet => {
+                let a = self.acceleration(self.v);
+                self.pos += self.v * dt + (a * dt.powi(2)) / 2.;
+                // The new velocity isn't known yet, it is predicted.
+                self.a = self.acceleration(self.v + a * dt);
+                self.v += (a + self.a) * (dt / 2.);
+            }
+            Integrator::Rk4 => {
+                let v1 = self.v;
+                let a1 = self.acceleration(v1);
+                let v2 = self.v + a1 * (dt / 2.);
+                let a2 = self.acceleration(v2);
+                let v3 = self.v + a2 * (dt / 2.);
+                let a3 = self.acceleration(v3);
+                let v4 = self.v + a3 * dt;
+                let a4 = self.acceleration(v4);
+
+                self.pos += (v1 + v2 * 2. + v3 * 2. + v4) * (dt / 6.);
+                self.v += (a1 + a2 * 2. + a3 * 2. + a4) * (dt / 6.);
+                self.a = self.acceleration(self.v);
+            }
+        }
     }
 
     /// Apply a new force on the object, updates the total force.
     pub fn apply_force(&mut self, force: Vec2) {
         self.tf += force;
     }
+    /// Apply a force during the next update only, for example to
+    /// push the object while a key is held.
+    pub fn apply_force_once(&mut self, force: Vec2) {
+        self.once += force;
+    }
+    /// Change the velocity instantly by `impulse / m` at the next
+    /// update, for example to jump.
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.impulse += impulse;
+    }
     /// Set the total force to the weight of the object.
     pub fn set_tf_to_w(&mut self) {
         self.tf = Vec2(0., self.m * self.g);
@@ -43,11 +164,13 @@ impl Physics {
     pub fn reset_tf(&mut self) {
         self.tf = Vec2(0., 0.);
     }
-    /// Reset acceleration, velocity and total force to 0.
+    /// Reset acceleration, velocity and all the forces to 0.
     pub fn reset_all(&mut self) {
         self.a = Vec2(0., 0.);
         self.v = Vec2(0., 0.);
         self.tf = Vec2(0., 0.);
+        self.once = Vec2::ZERO;
+        self.impulse = Vec2::ZERO;
     }
 
     /// The total force applied on the object, the weight is
@@ -97,4 +220,160 @@ impl Physics {
     pub fn w(&self) -> f64 {
         self.m * self.g
     }
+
+    pub fn linear_drag(&self) -> &f64 {
+        &self.linear_drag
+    }
+    pub fn linear_drag_mut(&mut self) -> &mut f64 {
+        &mut self.linear_drag
+    }
+    pub fn quadratic_drag(&self) -> &f64 {
+        &self.quadratic_drag
+    }
+    pub fn quadratic_drag_mut(&mut self) -> &mut f64 {
+        &mut self.quadratic_drag
+    }
+    pub fn ground_friction(&self) -> &f64 {
+        &self.ground_friction
+    }
+    pub fn ground_friction_mut(&mut self) -> &mut f64 {
+        &mut self.ground_friction
+    }
+    /// Whether the object rests on the ground, ground friction only
+    /// applies then. Set by `PhysicsWorld::step`.
+    pub fn grounded(&self) -> &bool {
+        &self.grounded
+    }
+    pub fn grounded_mut(&mut self) -> &mut bool {
+        &mut self.grounded
+    }
+    /// Infinite by default.
+    pub fn max_speed(&self) -> &Vec2 {
+        &self.max_speed
+    }
+    pub fn max_speed_mut(&mut self) -> &mut Vec2 {
+        &mut self.max_speed
+    }
+
+    pub fn integrator(&self) -> &Integrator {
+        &self.integrator
+    }
+    pub fn integrator_mut(&mut self) -> &mut Integrator {
+        &mut self.integrator
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::vec2::Vec2;
+
+    use super::{Integrator, Physics};
+
+    const G: f64 = 100.;
+    const V0: Vec2 = Vec2(30., -50.);
+
+    /// Throw a projectile for 2s with a large dt, return its error
+    /// on the position and how much its energy drifted.
+    fn throw(integrator: Integrator) -> (f64, f64) {
+        let mut physics = Physics::new(Vec2::ZERO, V0, 1., G);
+        *physics.integrator_mut() = integrator;
+        physics.set_tf_to_w();
+        // y points down, so does gravity.
+        let energy = |p: &Physics| p.v().dot_product(*p.v()) / 2. - G * p.pos().1;
+        let initial_energy = energy(&physics);
+
+        let dt = 0.1;
+        for _ in 0..20 {
+            physics.update(dt);
+        }
+        let t = 2.;
+        let expected = V0 * t + Vec2(0., G) * (t * t / 2.);
+        (
+            (*physics.pos() - expected).length(),
+            (energy(&physics) - initial_energy).abs(),
+        )
+    }
+
+    #[test]
+    fn projectile() {
+        let kinematic = throw(Integrator::Kinematic);
+        let euler = throw(Integrator::SemiImplicitEuler);
+        let verlet = throw(Integrator::VelocityVerlet);
+        let rk4 = throw(Integrator::Rk4);
+
+        // Off by G * dt^2 and G * dt^2 / 2 every update, in
+        // opposite directions.
+        assert!((kinematic.0 - 20.).abs() < 1e-9);
+        assert!((euler.0 - 10.).abs() < 1e-9);
+        assert!(kinematic.1 > 100. && euler.1 > 100.);
+
+        for (error, drift) in [verlet, rk4] {
+            assert!(error < 1e-9, "{}", error);
+            assert!(drift < 1e-9, "{}", drift);
+        }
+    }
+
+    #[test]
+    fn forces() {
+        let mut physics = Physics::new(Vec2::ZERO, Vec2::ZERO, 2., 0.);
+        physics.apply_force(Vec2(2., 0.));
+        physics.apply_force_once(Vec2(0., 4.));
+        physics.apply_impulse(Vec2(0., -10.));
+        *physics.integrator_mut() = Integrator::SemiImplicitEuler;
+
+        physics.update(1.);
+        assert_eq!(*physics.v(), Vec2(1., -3.));
+        assert_eq!(*physics.pos(), Vec2(1., -3.));
+        // Only the persistent force is left.
+        physics.update(1.);
+        assert_eq!(*physics.v(), Vec2(2., -3.));
+        assert_eq!(*physics.pos(), Vec2(3., -6.));
+
+        *physics.max_speed_mut() = Vec2(2.5, 1.);
+        physics.update(1.);
+        assert_eq!(*physics.v(), Vec2(2.5, -1.));
+        assert_eq!(*physics.pos(), Vec2(6., -7.));
+
+        // The impulse doesn't move the body faster than the max
+        // speed either.
+        let mut physics = Physics::new(Vec2::ZERO, Vec2::ZERO, 1., 0.);
+        *physics.max_speed_mut() = Vec2(10., 10.);
+        physics.apply_impulse(Vec2(1000., 0.));
+        physics.update(1.);
+        assert_eq!(*physics.v(), Vec2(10., 0.));
+        assert_eq!(*physics.pos(), Vec2(10., 0.));
+    }
+
+    #[test]
+    fn drag_and_friction() {
+        // Falls until the drag cancels the weight.
+        let mut physics = Physics::new(Vec2::ZERO, Vec2::ZERO, 1., G);
+        *physics.integrator_mut() = Integrator::Rk4;
+        *physics.linear_drag_mut() = 2.;
+        physics.set_tf_to_w();
+        for _ in 0..600 {
+            physics.update(1. / 60.);
+        }
+        assert!((physics.v().1 - G / 2.).abs() < 1e-6);
+
+        *physics.linear_drag_mut() = 0.;
+        *physics.quadratic_drag_mut() = 0.01;
+        for _ in 0..600 {
+            physics.update(1. / 60.);
+        }
+        assert!((physics.v().1 - (G / 0.01f64).sqrt()).abs() < 1e-6);
+
+        // Slides on the ground, decelerating by friction * g.
+        let mut physics = Physics::new(Vec2::ZERO, Vec2(10., 0.), 1., G);
+        *physics.ground_friction_mut() = 0.5;
+        *physics.grounded_mut() = true;
+        for _ in 0..6 {
+            physics.update(1. / 60.);
+        }
+        assert!((physics.v().0 - 5.).abs() < 1e-9);
+        for _ in 0..60 {
+            physics.update(1. / 60.);
+        }
+        assert_eq!(physics.v().0, 0.);
+    }
 }
@@ -0,0 +1,521 @@
+use crate::{
+    collision::{self, Hit},
+    object::{Boundaries, Object},
+    physics::Physics,
+    vec2::Vec2,
+};
+
+/// Identifies a body of a PhysicsWorld, it stays valid until the
+/// body is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BodyId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyKind {
+    /// Never moves, the ground and the walls.
+    Static,
+    /// Moved by its Physics and pushed by the other bodies.
+    Dynamic,
+}
+
+/// A Physics state paired with the Object used as its hitbox.
+#[derive(Debug, Clone)]
+pub struct Body {
+    physics: Physics,
+    object: Object,
+    kind: BodyKind,
+    /// How much of the speed is kept when bouncing, between 0 and 1.
+    restitution: f64,
+    /// How much the surfaces slow down the sliding bodies.
+    friction: f64,
+}
+
+impl Body {
+    /// Create a dynamic body, the position of `physics` is replaced
+    /// by the one of `object`.
+    pub fn dynamic(mut physics: Physics, object: Object) -> Self {
+        *physics.pos_mut() = *object.pos();
+        Body {
+            physics,
+            object,
+            kind: BodyKind::Dynamic,
+            restitution: 0.,
+            friction: 0.,
+        }
+    }
+
+    /// Create a static body.
+    pub fn fixed(object: Object) -> Self {
+        Body {
+            physics: Physics::new(*object.pos(), Vec2::ZERO, f64::INFINITY, 0.),
+            object,
+            kind: BodyKind::Static,
+            restitution: 0.,
+            friction: 0.,
+        }
+    }
+
+    /// Move the body to `pos`, its Physics and its Object.
+    pub fn set_pos(&mut self, pos: Vec2) {
+        *self.physics.pos_mut() = pos;
+        *self.object.pos_mut() = pos;
+    }
+
+    fn inverse_mass(&self) -> f64 {
+        match self.kind {
+            BodyKind::Static => 0.,
+            BodyKind::Dynamic => 1. / self.physics.m(),
+        }
+    }
+
+    pub fn physics(&self) -> &Physics {
+        &self.physics
+    }
+    /// Changing the position here doesn't move the Object, see
+    /// `Body::set_pos`.
+    pub fn physics_mut(&mut self) -> &mut Physics {
+        &mut self.physics
+    }
+    pub fn object(&self) -> &Object {
+        &self.object
+    }
+    pub fn kind(&self) -> &BodyKind {
+        &self.kind
+    }
+    pub fn restitution(&self) -> &f64 {
+        &self.restitution
+    }
+    pub fn restitution_mut(&mut self) -> &mut f64 {
+        &mut self.restitution
+    }
+    pub fn friction(&self) -> &f64 {
+        &self.friction
+    }
+    pub fn friction_mut(&mut self) -> &mut f64 {
+        &mut self.friction
+    }
+}
+
+/// Two bodies touching or overlapping, `a` is the lowest id.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub a: BodyId,
+    pub b: BodyId,
+    /// Points from `b` towards `a`.
+    pub normal: Vec2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactEvent {
+    /// The bodies started touching during the step.
+    Begin(BodyId, BodyId),
+    /// The bodies stopped touching during the step.
+    End(BodyId, BodyId),
+}
+
+/// Owns bodies and steps them together: dynamic bodies move
+/// without going through static ones and push each other.
+///
+/// Like in most engines, the restitution of a contact is the
+/// highest of the two bodies and its friction is the geometric mean
+/// of theirs.
+#[derive(Debug, Clone)]
+pub struct PhysicsWorld {
+    bodies: Vec<Option<Body>>,
+    contacts: Vec<Contact>,
+    events: Vec<ContactEvent>,
+    /// Below this speed (in px/s) towards each other, bodies don't
+    /// bounce: resting bodies would jitter.
+    resting_speed: f64,
+
+    /// The hitboxes of the static bodies and their index, filled
+    /// at the start of every step.
+    obstacles: Vec<Object>,
+    obstacle_indices: Vec<usize>,
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        PhysicsWorld::new()
+    }
+}
+
+impl PhysicsWorld {
+    pub fn new() -> Self {
+        PhysicsWorld {
+            bodies: Vec::new(),
+            contacts: Vec::new(),
+            events: Vec::new(),
+            resting_speed: 10.,
+            obstacles: Vec::new(),
+            obstacle_indices: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, body: Body) -> BodyId {
+        self.bodies.push(Some(body));
+        BodyId(self.bodies.len() - 1)
+    }
+
+    /// Remove a body, its contacts end without events.
+    pub fn remove(&mut self, id: BodyId) -> Option<Body> {
+        self.contacts.retain(|c| c.a != id && c.b != id);
+        self.bodies.get_mut(id.0)?.take()
+    }
+
+    pub fn body(&self, id: BodyId) -> Option<&Body> {
+        self.bodies.get(id.0)?.as_ref()
+    }
+    pub fn body_mut(&mut self, id: BodyId) -> Option<&mut Body> {
+        self.bodies.get_mut(id.0)?.as_mut()
+    }
+    pub fn bodies(&self) -> impl Iterator<Item = (BodyId, &Body)> {
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(i, body)| Some((BodyId(i), body.as_ref()?)))
+    }
+
+    /// The contacts found by the last step.
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+    /// The contacts that began or ended during the last step.
+    pub fn events(&self) -> &[ContactEvent] {
+        &self.events
+    }
+
+    pub fn resting_speed(&self) -> &f64 {
+        &self.resting_speed
+    }
+    pub fn resting_speed_mut(&mut self) -> &mut f64 {
+        &mut self.resting_speed
+    }
+
+    /// The restitution and the friction of a contact.
+    fn materials(a: &Body, b: &Body) -> (f64, f64) {
+        (
+            a.restitution.max(b.restitution),
+            (a.friction * b.friction).sqrt(),
+        )
+    }
+
+    /// The velocity `v` of a body after hitting a surface with
+    /// `normal` moving at `surface_v`.
+    fn bounce(&self, v: Vec2, surface_v: Vec2, normal: Vec2, materials: (f64, f64)) -> Vec2 {
+        let (restitution, friction) = materials;
+        let relative = v - surface_v;
+        let into = relative.dot_product(normal);
+        if into >= 0. {
+            return v;
+        }
+        let restitution = if -into < self.resting_speed {
+            0.
+        } else {
+            restitution
+        };
+
+        let tangent = normal.orthogonal();
+        let along = relative.dot_product(tangent);
+        let slowed = along.signum() * (along.abs() - friction * -into).max(0.);
+        surface_v + tangent * slowed - normal * (into * restitution)
+    }
+
+    /// Move the dynamic bodies by `dt` seconds and update the
+    /// contacts.
+    pub fn step(&mut self, dt: f64) {
+        self.obstacles.clear();
+        self.obstacle_indices.clear();
+        for (i, body) in self.bodies.iter().enumerate() {
+            if let Some(body) = body {
+                if body.kind == BodyKind::Static {
+                    self.obstacles.push(body.object.clone());
+                    self.obstacle_indices.push(i);
+                }
+            }
+        }
+
+        for i in 0..self.bodies.len() {
+            if let Some(body) = &self.bodies[i] {
+                if body.kind == BodyKind::Dynamic {
+                    self.move_body(i, dt);
+                }
+            }
+        }
+        self.separate_dynamic();
+        self.update_contacts();
+    }
+
+    /// Move the dynamic body `i` without going through the static
+    /// bodies.
+    fn move_body(&mut self, i: usize, dt: f64) {
+        let mut body = self.bodies[i].take().unwrap();
+
+        let start = *body.object.pos();
+        body.physics.update(dt);
+        let movement = collision::move_and_slide(
+            &mut body.object,
+            *body.physics.pos() - start,
+            &self.obstacles,
+        );
+        *body.physics.pos_mut() = *body.object.pos();
+        *body.physics.grounded_mut() = movement.on_floor();
+
+        for (k, Hit { normal, .. }) in movement.hits {
+            let other = self.bodies[self.obstacle_indices[k]].as_ref().unwrap();
+            let materials = PhysicsWorld::materials(&body, other);
+            *body.physics.v_mut() = self.bounce(*body.physics.v(), Vec2::ZERO, normal, materials);
+        }
+        self.bodies[i] = Some(body);
+    }
+
+    /// Push the overlapping dynamic bodies apart, in proportion to
+    /// their inverse mass, and exchange their momentum. A body
+    /// blocked by a static one leaves the rest of the push to the
+    /// other.
+    fn separate_dynamic(&mut self) {
+        for i in 0..self.bodies.len() {
+            for j in i + 1..self.bodies.len() {
+                let (left, right) = self.bodies.split_at_mut(j);
+                let (Some(a), Some(b)) = (&mut left[i], &mut right[0]) else {
+                    continue;
+                };
+                if a.kind != BodyKind::Dynamic || b.kind != BodyKind::Dynamic {
+                    continue;
+                }
+                let Some((normal, depth)) = penetration(&a.object, &b.object) else {
+                    continue;
+                };
+
+                let (inv_a, inv_b) = (a.inverse_mass(), b.inverse_mass());
+                // Neither can be pushed.
+                if inv_a + inv_b == 0. {
+                    continue;
+                }
+                let share = inv_a / (inv_a + inv_b);
+                let moved = collision::move_and_slide(
+                    &mut a.object,
+                    normal * (depth * share),
+                    &self.obstacles,
+                )
+                .moved;
+                let rest = depth - moved.dot_product(normal);
+                collision::move_and_slide(&mut b.object, normal * -rest, &self.obstacles);
+                a.set_pos(*a.object.pos());
+                b.set_pos(*b.object.pos());
+
+                let into = (*a.physics.v() - *b.physics.v()).dot_product(normal);
+                if into < 0. {
+                    let (restitution, _) = PhysicsWorld::materials(a, b);
+                    let restitution = if -into < self.resting_speed {
+                        0.
+                    } else {
+                        restitution
+                    };
+                    let impulse = -(1. + restitution) * into / (inv_a + inv_b);
+                    *a.physics.v_mut() += normal * (impulse * inv_a);
+                    *b.physics.v_mut() -= normal * (impulse * inv_b);
+                }
+            }
+        }
+    }
+
+    /// Find the touching bodies and emit the events of the contacts
+    /// that changed.
+    fn update_contacts(&mut self) {
+        let previous = std::mem::take(&mut self.contacts);
+        for (i, a) in self.bodies.iter().enumerate() {
+            for (j, b) in self.bodies.iter().enumerate().skip(i + 1) {
+                let (Some(a), Some(b)) = (a, b) else {
+                    continue;
+                };
+                if a.kind == BodyKind::Static && b.kind == BodyKind::Static {
+                    continue;
+                }
+                if a.object.intersecting_with(&b.object) {
+                    self.contacts.push(Contact {
+                        a: BodyId(i),
+                        b: BodyId(j),
+                        normal: contact_normal(&a.object, &b.object),
+                    });
+                }
+            }
+        }
+
+        let same = |c: &Contact, d: &Contact| c.a == d.a && c.b == d.b;
+        self.events.clear();
+        for contact in &self.contacts {
+            if !previous.iter().any(|p| same(p, contact)) {
+                self.events.push(ContactEvent::Begin(contact.a, contact.b));
+            }
+        }
+        for contact in &previous {
+            if !self.contacts.iter().any(|c| same(c, contact)) {
+                self.events.push(ContactEvent::End(contact.a, contact.b));
+            }
+        }
+    }
+}
+
+/// How deep `a` and `b` overlap along the axis they overlap the
+/// least, with the normal pointing from `b` towards `a`. None if
+/// they only touch or don't overlap at all.
+fn penetration(a: &Object, b: &Object) -> Option<(Vec2, f64)> {
+    let (ba, bb) = (a.boundaries(), b.boundaries());
+    let x = (ba.right - bb.left).min(bb.right - ba.left);
+    let y = (ba.bottom - bb.top).min(bb.bottom - ba.top);
+    if x <= 0. || y <= 0. {
+        return None;
+    }
+    Some((contact_normal(a, b), x.min(y)))
+}
+
+/// The normal of the contact of `a` and `b`, pointing from `b`
+/// towards `a`, along the axis they overlap the least.
+fn contact_normal(a: &Object, b: &Object) -> Vec2 {
+    let (ba, bb) = (a.boundaries(), b.boundaries());
+    let x = (ba.right - bb.left).min(bb.right - ba.left);
+    let y = (ba.bottom - bb.top).min(bb.bottom - ba.top);
+    let center = |b: Boundaries| Vec2((b.left + b.right) / 2., (b.top + b.bottom) / 2.);
+    let d = center(ba) - center(bb);
+    if x < y {
+        Vec2(if d.0 < 0. { -1. } else { 1. }, 0.)
+    } else {
+        Vec2(0., if d.1 < 0. { -1. } else { 1. })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{object::Object, physics::Physics, vec2::Vec2};
+
+    use super::{Body, BodyId, ContactEvent, PhysicsWorld};
+
+    const DT: f64 = 1. / 60.;
+
+    fn ball(pos: Vec2, v: Vec2) -> Body {
+        let mut physics = Physics::new(pos, v, 1., 300.);
+        physics.set_tf_to_w();
+        Body::dynamic(physics, Object::new(pos, Vec2(2., 2.), None))
+    }
+
+    fn floor() -> Body {
+        Body::fixed(Object::new(Vec2(-100., 50.), Vec2(200., 4.), None))
+    }
+
+    #[test]
+    fn bounce_and_events() {
+        let mut world = PhysicsWorld::new();
+        let floor = world.add(floor());
+        let ball = world.add(ball(Vec2(0., 0.), Vec2::ZERO));
+        *world.body_mut(ball).unwrap().restitution_mut() = 0.5;
+
+        let mut events = Vec::new();
+        let mut lowest = 0f64;
+        for _ in 0..120 {
+            world.step(DT);
+            events.extend_from_slice(world.events());
+            let body = world.body(ball).unwrap();
+            lowest = lowest.max(body.object().boundaries().bottom);
+        }
+        // Never went through the floor, bounced and came back.
+        assert_eq!(lowest, world.body(floor).unwrap().object().boundaries().top);
+        assert_eq!(
+            events[..2],
+            [
+                ContactEvent::Begin(floor, ball),
+                ContactEvent::End(floor, ball)
+            ]
+        );
+
+        // Comes to rest on the floor.
+        for _ in 0..600 {
+            world.step(DT);
+        }
+        assert!(world.events().is_empty());
+        assert_eq!(world.contacts().len(), 1);
+        // From the ball towards the floor.
+        assert_eq!(world.contacts()[0].normal, Vec2(0., 1.));
+        assert_eq!(world.body(ball).unwrap().physics().v().1, 0.);
+    }
+
+    #[test]
+    fn friction() {
+        let mut world = PhysicsWorld::new();
+        let floor = world.add(floor());
+        *world.body_mut(floor).unwrap().friction_mut() = 1.;
+        // Resting on the floor.
+        let slippery = world.add(ball(Vec2(0., 47.), Vec2(40., 0.)));
+        let rough = world.add(ball(Vec2(-50., 47.), Vec2(40., 0.)));
+        *world.body_mut(rough).unwrap().friction_mut() = 1.;
+
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        let v = |id: BodyId| world.body(id).unwrap().physics().v().0;
+        assert_eq!(v(slippery), 40.);
+        assert_eq!(v(rough), 0.);
+    }
+
+    #[test]
+    fn dynamic_bodies() {
+        let mut world = PhysicsWorld::new();
+        let mut a = ball(Vec2(0., 0.), Vec2(30., 0.));
+        let mut b = ball(Vec2(10., 0.), Vec2(-30., 0.));
+        for body in [&mut a, &mut b] {
+            body.physics_mut().reset_tf();
+            *body.restitution_mut() = 1.;
+        }
+        let (a, b) = (world.add(a), world.add(b));
+
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        // Same masses, the velocities are exchanged.
+        assert_eq!(*world.body(a).unwrap().physics().v(), Vec2(-30., 0.));
+        assert_eq!(*world.body(b).unwrap().physics().v(), Vec2(30., 0.));
+        assert!(!world
+            .body(a)
+            .unwrap()
+            .object()
+            .intersecting_with(world.body(b).unwrap().object()));
+        assert!(world.remove(a).is_some() && world.body(a).is_none());
+    }
+
+    #[test]
+    fn separation() {
+        let mut world = PhysicsWorld::new();
+        let wall = world.add(Body::fixed(Object::new(
+            Vec2(10., -20.),
+            Vec2(4., 40.),
+            None,
+        )));
+        // `a` touches the wall and `b` overlaps it by a pixel.
+        let mut a = ball(Vec2(7., 0.), Vec2::ZERO);
+        let mut b = ball(Vec2(5., 0.), Vec2::ZERO);
+        for body in [&mut a, &mut b] {
+            body.physics_mut().reset_tf();
+        }
+        let (a, b) = (world.add(a), world.add(b));
+
+        world.step(DT);
+        let boundaries = |id: BodyId| world.body(id).unwrap().object().boundaries();
+        // The wall doesn't let `a` move, `b` is pushed instead.
+        assert_eq!(boundaries(a).right, boundaries(wall).left);
+        assert_eq!(boundaries(b).right, boundaries(a).left);
+        assert_eq!(*world.body(b).unwrap().physics().pos(), Vec2(4., 0.));
+
+        // Neither can be pushed, they stay where they are.
+        let mut world = PhysicsWorld::new();
+        for x in [0., 1.] {
+            let physics = Physics::new(Vec2(x, 0.), Vec2::ZERO, f64::INFINITY, 0.);
+            world.add(Body::dynamic(
+                physics,
+                Object::new(Vec2(x, 0.), Vec2(2., 2.), None),
+            ));
+        }
+        world.step(DT);
+        let positions: Vec<_> = world.bodies().map(|(_, b)| *b.physics().pos()).collect();
+        assert_eq!(positions, [Vec2(0., 0.), Vec2(1., 0.)]);
+    }
+}
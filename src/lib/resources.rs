@@ -1,40 +1,227 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 use crate::{
     color::Color,
+    font::Font,
     mat::{Mat, MatSlice},
 };
 
-const ASSETS_PATH: &str = "assets";
+/// The default assets directory, relative to the current working
+/// directory.
+pub const ASSETS_PATH: &str = "assets";
 
-/// Import sprites from spritesheet, `sprite_dims` represents
-/// the dimensions of a sprite (that must have the same dims)
-/// and `spritesheet_dims` represents the number of rows and
-/// columns that the spritesheet holds.
-pub fn import_spritesheet(
-    path: &str,
+#[derive(Debug)]
+pub enum ResourceError {
+    /// The file couldn't be read or written.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The file couldn't be decoded as an image.
+    Decode {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+    /// The image couldn't be encoded to the format of its
+    /// extension.
+    Encode {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+    /// The image doesn't have the expected dimensions.
+    DimensionMismatch {
+        path: PathBuf,
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// The spritesheet holds less sprites than requested.
+    SpritesheetOutOfRange {
+        path: PathBuf,
+        n_sprites: usize,
+        capacity: usize,
+    },
+    /// A description file (for example a font's character map) is
+    /// invalid.
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl ResourceError {
+    /// The path of the resource that failed to load.
+    pub fn path(&self) -> &Path {
+        match self {
+            ResourceError::Io { path, .. }
+            | ResourceError::Decode { path, .. }
+            | ResourceError::Encode { path, .. }
+            | ResourceError::DimensionMismatch { path, .. }
+            | ResourceError::SpritesheetOutOfRange { path, .. }
+            | ResourceError::Parse { path, .. } => path,
+        }
+    }
+}
+
+impl Display for ResourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResourceError::Io { path, source } => {
+                write!(f, "failed to access {}: {}", path.display(), source)
+            }
+            ResourceError::Decode { path, source } => {
+                write!(f, "failed to decode {}: {}", path.display(), source)
+            }
+            ResourceError::Encode { path, source } => {
+                write!(f, "failed to encode {}: {}", path.display(), source)
+            }
+            ResourceError::DimensionMismatch {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has dimensions {:?}, expected {:?}",
+                path.display(),
+                found,
+                expected
+            ),
+            ResourceError::SpritesheetOutOfRange {
+                path,
+                n_sprites,
+                capacity,
+            } => write!(
+                f,
+                "{} holds {} sprites but {} were requested",
+                path.display(),
+                capacity,
+                n_sprites
+            ),
+            ResourceError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+impl std::error::Error for ResourceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ResourceError::Io { source, .. } => Some(source),
+            ResourceError::Decode { source, .. } => Some(source),
+            ResourceError::Encode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Create a Mat from an RGBA image.
+pub fn image_to_mat(image: &image::RgbaImage) -> Mat<Color> {
+    Mat::from_vec(
+        image
+            .as_raw()
+            .chunks(4)
+            .map(|v| Color::from_bytes([v[0], v[1], v[2], v[3]]))
+            .collect::<Vec<_>>(),
+        (image.width() as usize, image.height() as usize),
+    )
+}
+
+/// Create an RGBA image from a MatSlice.
+pub fn mat_to_image(mat: &impl MatSlice<Color>) -> image::RgbaImage {
+    let (width, height) = *mat.slice_dims();
+    image::RgbaImage::from_fn(width as u32, height as u32, |x, y| {
+        image::Rgba(mat[(x as usize, y as usize)].to_bytes())
+    })
+}
+
+fn image_result(
+    path: &Path,
+    result: image::ImageResult<image::DynamicImage>,
+) -> Result<Mat<Color>, ResourceError> {
+    match result {
+        Ok(image) => Ok(image_to_mat(&image.to_rgba8())),
+        Err(image::ImageError::IoError(source)) => Err(ResourceError::Io {
+            path: path.to_owned(),
+            source,
+        }),
+        Err(source) => Err(ResourceError::Decode {
+            path: path.to_owned(),
+            source,
+        }),
+    }
+}
+
+/// Open the image at `path` (not relative to the assets
+/// directory), its dimensions are the ones of the file.
+pub fn open_image(path: impl AsRef<Path>) -> Result<Mat<Color>, ResourceError> {
+    let path = path.as_ref();
+    image_result(path, image::open(path))
+}
+
+/// Save `image` at `path` (not relative to the assets directory),
+/// the format is chosen from the extension.
+pub fn save_image(
+    path: impl AsRef<Path>,
+    image: &impl MatSlice<Color>,
+) -> Result<(), ResourceError> {
+    let path = path.as_ref();
+    match mat_to_image(image).save(path) {
+        Ok(()) => Ok(()),
+        Err(image::ImageError::IoError(source)) => Err(ResourceError::Io {
+            path: path.to_owned(),
+            source,
+        }),
+        Err(source) => Err(ResourceError::Encode {
+            path: path.to_owned(),
+            source,
+        }),
+    }
+}
+
+/// Decode an image from the content of a file, `path` is only used
+/// in errors.
+pub fn decode_image(path: impl AsRef<Path>, bytes: &[u8]) -> Result<Mat<Color>, ResourceError> {
+    image_result(path.as_ref(), image::load_from_memory(bytes))
+}
+
+/// Split a spritesheet into sprites, see `import_spritesheet`.
+pub(crate) fn split_spritesheet(
+    path: &Path,
+    spritesheet_image: &Mat<Color>,
     sprite_dims: (usize, usize),
-    spritesheet_dims: (usize, usize),
     n_sprites: usize,
-) -> Vec<Mat<Color>> {
-    let image = image::open(PathBuf::from_str(ASSETS_PATH).unwrap().join(path))
-        .unwrap()
-        .to_rgba8();
-    let image_pixels: Vec<Color> = image
-        .as_raw()
-        .chunks(4)
-        .map(|v| Color {
-            r: v[0],
-            g: v[1],
-            b: v[2],
-            a: v[3],
-        })
-        .collect();
-    let image_dims = (
-        sprite_dims.0 * spritesheet_dims.0,
-        sprite_dims.1 * spritesheet_dims.1,
-    );
-    let spritesheet_image = Mat::from_vec(image_pixels, image_dims);
+) -> Result<Vec<Mat<Color>>, ResourceError> {
+    let image_dims = *spritesheet_image.dims();
+    if sprite_dims.0 == 0
+        || sprite_dims.1 == 0
+        || !image_dims.0.is_multiple_of(sprite_dims.0)
+        || !image_dims.1.is_multiple_of(sprite_dims.1)
+    {
+        return Err(ResourceError::DimensionMismatch {
+            path: path.to_owned(),
+            expected: (
+                image_dims.0 / sprite_dims.0.max(1) * sprite_dims.0,
+                image_dims.1 / sprite_dims.1.max(1) * sprite_dims.1,
+            ),
+            found: image_dims,
+        });
+    }
+
+    let spritesheet_dims = (image_dims.0 / sprite_dims.0, image_dims.1 / sprite_dims.1);
+    let capacity = spritesheet_dims.0 * spritesheet_dims.1;
+    if n_sprites > capacity {
+        return Err(ResourceError::SpritesheetOutOfRange {
+            path: path.to_owned(),
+            n_sprites,
+            capacity,
+        });
+    }
 
     let mut images = Vec::new();
 
@@ -54,45 +241,92 @@ pub fn import_spritesheet(
         }
     }
 
-    images
+    Ok(images)
 }
 
-pub fn import_sprites(paths: &[&str], dims: (usize, usize)) -> Vec<Mat<Color>> {
-    let mut images = Vec::new();
+/// Import sprites from spritesheet, `sprite_dims` represents
+/// the dimensions of a sprite (that must all have the same
+/// dims), the number of rows and columns of the spritesheet is
+/// deduced from the dimensions of the image.
+pub fn import_spritesheet(
+    path: &str,
+    sprite_dims: (usize, usize),
+    n_sprites: usize,
+) -> Result<Vec<Mat<Color>>, ResourceError> {
+    let path = Path::new(ASSETS_PATH).join(path);
+    split_spritesheet(&path, &open_image(&path)?, sprite_dims, n_sprites)
+}
+
+/// Import several sprites, they must all have the same
+/// dimensions.
+pub fn import_sprites(paths: &[&str]) -> Result<Vec<Mat<Color>>, ResourceError> {
+    let mut images: Vec<Mat<Color>> = Vec::new();
     for path in paths {
-        let image: image::ImageBuffer<image::Rgba<u8>, Vec<u8>> =
-            image::open(path).unwrap().to_rgba8();
-        images.push(Mat::from_vec(
-            image
-                .as_raw()
-                .chunks(4)
-                .map(|v| Color {
-                    r: v[0],
-                    g: v[1],
-                    b: v[2],
-                    a: v[3],
-                })
-                .collect::<Vec<_>>(),
-            dims,
-        ));
+        let path = Path::new(ASSETS_PATH).join(path);
+        let image = open_image(&path)?;
+        if let Some(first) = images.first() {
+            if first.dims() != image.dims() {
+                return Err(ResourceError::DimensionMismatch {
+                    path,
+                    expected: *first.dims(),
+                    found: *image.dims(),
+                });
+            }
+        }
+        images.push(image);
     }
-    images
+    Ok(images)
 }
 
-pub fn import_sprite(path: &str, dims: (usize, usize)) -> Mat<Color> {
-    Mat::from_vec(
-        image::open(PathBuf::from_str(ASSETS_PATH).unwrap().join(path))
-            .unwrap()
-            .to_rgba8()
-            .as_raw()
-            .chunks(4)
-            .map(|v| Color {
-                r: v[0],
-                g: v[1],
-                b: v[2],
-                a: v[3],
+pub fn import_sprite(path: &str) -> Result<Mat<Color>, ResourceError> {
+    open_image(Path::new(ASSETS_PATH).join(path))
+}
+
+/// Import a bitmap font from its glyph atlas and its character
+/// map, see `Font` for the format of the character map.
+pub fn import_font(atlas_path: &str, charmap_path: &str) -> Result<Font, ResourceError> {
+    let atlas = import_sprite(atlas_path)?;
+    let path = Path::new(ASSETS_PATH).join(charmap_path);
+    let description = std::fs::read_to_string(&path).map_err(|source| ResourceError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    Font::new(path, &atlas, &description)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::{color::Color, mat::Mat};
+
+    use super::{import_sprite, split_spritesheet, ResourceError};
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            import_sprite("does/not/exist.png"),
+            Err(ResourceError::Io { .. })
+        ));
+        assert!(matches!(
+            import_sprite("LICENSE"),
+            Err(ResourceError::Decode { .. })
+        ));
+
+        let sheet = Mat::filled_with(Color::WHITE, (10, 4));
+        let path = Path::new("sheet.png");
+        assert_eq!(split_spritesheet(path, &sheet, (5, 2), 4).unwrap().len(), 4);
+        assert!(matches!(
+            split_spritesheet(path, &sheet, (3, 2), 1),
+            Err(ResourceError::DimensionMismatch {
+                expected: (9, 4),
+                found: (10, 4),
+                ..
             })
-            .collect::<Vec<_>>(),
-        dims,
-    )
+        ));
+        assert!(matches!(
+            split_spritesheet(path, &sheet, (5, 2), 5),
+            Err(ResourceError::SpritesheetOutOfRange { capacity: 4, .. })
+        ));
+    }
 }
@@ -0,0 +1,179 @@
+//! Golden-image testing: render something to a Mat and compare it
+//! with a reference PNG stored in `tests/snapshots`.
+//!
+//! Run the tests with `PIXEL_GAME_BLESS=1` to create or update the
+//! reference images instead of comparing against them.
+
+use std::{
+    env,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    color::Color,
+    mat::{Mat, MatSlice},
+    resources::{image_to_mat, mat_to_image},
+};
+
+/// The directory holding the reference images.
+pub const SNAPSHOTS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots");
+
+/// The environment variable enabling bless mode.
+pub const BLESS_VAR: &str = "PIXEL_GAME_BLESS";
+
+/// Color used in diff images for pixels that don't match.
+const DIFF_COLOR: Color = Color::new(255, 0, 255, 255);
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// There is no reference image for this snapshot.
+    Missing(PathBuf),
+    /// The reference image and the rendered image don't have the
+    /// same dimensions.
+    DimsMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// Some pixels differ more than the tolerance allows, the
+    /// diff image highlights them.
+    PixelsMismatch {
+        count: usize,
+        diff_path: PathBuf,
+    },
+    Image(image::ImageError),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Missing(path) => write!(
+                f,
+                "missing reference image {}, run with {}=1 to create it",
+                path.display(),
+                BLESS_VAR
+            ),
+            SnapshotError::DimsMismatch { expected, found } => write!(
+                f,
+                "expected dimensions {:?} but found {:?}",
+                expected, found
+            ),
+            SnapshotError::PixelsMismatch { count, diff_path } => {
+                write!(f, "{} pixels differ, see {}", count, diff_path.display())
+            }
+            SnapshotError::Image(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<image::ImageError> for SnapshotError {
+    fn from(value: image::ImageError) -> Self {
+        SnapshotError::Image(value)
+    }
+}
+
+/// Return true if bless mode is enabled.
+pub fn blessing() -> bool {
+    env::var(BLESS_VAR).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Path of the reference image for the snapshot `name`.
+pub fn reference_path(name: &str) -> PathBuf {
+    Path::new(SNAPSHOTS_PATH).join(format!("{}.png", name))
+}
+
+/// Path of the diff image written when the snapshot `name` doesn't
+/// match.
+pub fn diff_path(name: &str) -> PathBuf {
+    Path::new(SNAPSHOTS_PATH).join(format!("{}.diff.png", name))
+}
+
+fn channels_within(a: Color, b: Color, tolerance: u8) -> bool {
+    a.to_bytes()
+        .iter()
+        .zip(b.to_bytes())
+        .all(|(a, b)| a.abs_diff(b) <= tolerance)
+}
+
+/// Create an image of `reference` where the pixels that differ from
+/// `image` by more than `tolerance` are highlighted, return it with
+/// the number of such pixels.
+pub fn diff(
+    reference: &impl MatSlice<Color>,
+    image: &impl MatSlice<Color>,
+    tolerance: u8,
+) -> (Mat<Color>, usize) {
+    let mut count = 0;
+    let mut output = Mat::filled_with(Color::TRANSPARENT, *reference.slice_dims());
+    output.fill_with_r(|index| {
+        let (expected, found) = (reference[index], image[index]);
+        if channels_within(expected, found, tolerance) {
+            // Faded reference so the highlighted pixels stand out.
+            let gray = ((expected.r as u16 + expected.g as u16 + expected.b as u16) / 3) as u8;
+            Color::new(gray, gray, gray, expected.a / 4)
+        } else {
+            count += 1;
+            DIFF_COLOR
+        }
+    });
+    (output, count)
+}
+
+/// Compare `image` with the reference image of the snapshot `name`,
+/// each channel of each pixel may differ by at most `tolerance`.
+///
+/// In bless mode the reference image is overwritten instead.
+pub fn compare(
+    name: &str,
+    image: &impl MatSlice<Color>,
+    tolerance: u8,
+) -> Result<(), SnapshotError> {
+    let path = reference_path(name);
+
+    if blessing() {
+        std::fs::create_dir_all(SNAPSHOTS_PATH).map_err(image::ImageError::IoError)?;
+        mat_to_image(image).save(&path)?;
+        let _ = std::fs::remove_file(diff_path(name));
+        return Ok(());
+    }
+
+    if !path.exists() {
+        return Err(SnapshotError::Missing(path));
+    }
+    let reference = image_to_mat(&image::open(&path)?.to_rgba8());
+
+    if reference.dims() != image.slice_dims() {
+        return Err(SnapshotError::DimsMismatch {
+            expected: *reference.dims(),
+            found: *image.slice_dims(),
+        });
+    }
+
+    let (diff_image, count) = diff(&reference, image, tolerance);
+    let diff_path = diff_path(name);
+    if count > 0 {
+        mat_to_image(&diff_image).save(&diff_path)?;
+        Err(SnapshotError::PixelsMismatch { count, diff_path })
+    } else {
+        let _ = std::fs::remove_file(diff_path);
+        Ok(())
+    }
+}
+
+/// Panic if `image` doesn't match the reference image of the
+/// snapshot `name` exactly.
+#[track_caller]
+pub fn assert_snapshot(name: &str, image: &impl MatSlice<Color>) {
+    assert_snapshot_with_tolerance(name, image, 0)
+}
+
+/// Panic if `image` doesn't match the reference image of the
+/// snapshot `name` within `tolerance`.
+#[track_caller]
+pub fn assert_snapshot_with_tolerance(name: &str, image: &impl MatSlice<Color>, tolerance: u8) {
+    if let Err(error) = compare(name, image, tolerance) {
+        panic!("snapshot \"{}\" failed: {}", name, error)
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::Index;
 
 use crate::{
     color::Color,
-    mat::{Mat, MatSlice, SlicedMat},
+    mat::{Mat, MatSlice, Orientation, SlicedMat},
 };
 
 pub struct UvMap {
@@ -14,7 +14,7 @@ impl UvMap {
         UvMap { texture }
     }
 
-    pub fn render<'a>(&self, image: &impl MatSlice<Color>) -> Mat<Color> {
+    pub fn render(&self, image: &impl MatSlice<Color>) -> Mat<Color> {
         let dims = *image.slice_dims();
         let mut output = Mat::filled_with(Color::TRANSPARENT, dims);
         for x in 0..dims.0 {
@@ -27,6 +27,15 @@ impl UvMap {
         }
         output
     }
+
+    /// Like `UvMap::render` but the texture is looked up when the
+    /// pixels are read instead of creating a new Mat.
+    pub fn rendered<'a>(&'a self, image: SlicedMat<'a, Color>) -> UvRendered<'a> {
+        UvRendered {
+            map_slice: self.texture.as_slice(),
+            slice: image,
+        }
+    }
 }
 
 pub struct UvRendered<'a> {
@@ -34,6 +43,43 @@ pub struct UvRendered<'a> {
     slice: SlicedMat<'a, Color>,
 }
 
+impl<'a> UvRendered<'a> {
+    fn reorient(&self, orientation: Orientation) -> Self {
+        UvRendered {
+            map_slice: self.map_slice,
+            slice: self.slice.reorient(orientation),
+        }
+    }
+
+    /// View of the rendered image rotated 90° clockwise.
+    pub fn rotate_90(&self) -> Self {
+        self.reorient(self.slice.orientation().rotate_90())
+    }
+
+    /// View of the rendered image rotated 180°.
+    pub fn rotate_180(&self) -> Self {
+        self.reorient(self.slice.orientation().rotate_180())
+    }
+
+    /// View of the rendered image rotated 270° clockwise.
+    pub fn rotate_270(&self) -> Self {
+        self.reorient(self.slice.orientation().rotate_270())
+    }
+
+    /// View of the rendered image with its axes swapped.
+    pub fn transposed(&self) -> Self {
+        self.reorient(self.slice.orientation().transpose())
+    }
+
+    /// View of the rendered image scaled up `factor` times.
+    pub fn scale(&self, factor: usize) -> Self {
+        UvRendered {
+            map_slice: self.map_slice,
+            slice: self.slice.scale_up(factor),
+        }
+    }
+}
+
 impl<'a> MatSlice<Color> for UvRendered<'a> {
     fn slice_index(&self) -> &(usize, usize) {
         self.slice.slice_index()
@@ -41,8 +87,11 @@ impl<'a> MatSlice<Color> for UvRendered<'a> {
     fn slice_dims(&self) -> &(usize, usize) {
         self.slice.slice_dims()
     }
-    fn flip(&self) -> &(bool, bool) {
-        self.slice.flip()
+    fn orientation(&self) -> &Orientation {
+        self.slice.orientation()
+    }
+    fn factor(&self) -> usize {
+        self.slice.factor()
     }
     fn mat(&self) -> &Mat<Color> {
         self.slice.mat()
@@ -53,21 +102,11 @@ impl<'a> Index<(usize, usize)> for UvRendered<'a> {
     type Output = Color;
 
     fn index(&self, index: (usize, usize)) -> &Self::Output {
-        let slice_index = self.slice.slice_index();
-        let slice_dims = self.slice.slice_dims();
-        let index = (
-            if self.slice.flip().0 {
-                slice_index.0 + slice_dims.0 - 1 - index.0
-            } else {
-                slice_index.0 + index.0
-            },
-            if self.slice.flip().1 {
-                slice_index.1 + slice_dims.1 - 1 - index.1
-            } else {
-                slice_index.1 + index.1
-            },
-        );
-        let Color { r, g, .. } = self.slice.mat()[index];
-        &self.map_slice[(r as usize, g as usize)]
+        let Color { r, g, a, .. } = self.slice[index];
+        if a == 255 {
+            &self.map_slice[(r as usize, g as usize)]
+        } else {
+            &Color::TRANSPARENT
+        }
     }
 }
@@ -36,13 +36,13 @@ impl Vec2 {
 
     /// Return a vector of the same length but orthogonal to itself.
     pub fn orthogonal(&self) -> Vec2 {
-        return Vec2(self.1, -self.0);
+        Vec2(self.1, -self.0)
     }
 
     /// Return the dot (or scalar) product of the Vec2 with another
     /// one.
     pub fn dot_product(&self, other: Vec2) -> f64 {
-        return self.0 * other.0 + self.1 * other.1;
+        self.0 * other.0 + self.1 * other.1
     }
 
     // fn project(poly: &[Vector], axis: Vector) -> Vector {
@@ -0,0 +1,267 @@
+use pixel_game_lib::{
+    color::{BlendMode, Color},
+    font::{Align, TextOptions},
+    game::{GameBuilder, Layer},
+    mat::{Mat, MatSlice},
+    resources::{import_font, import_sprite},
+    snapshot::assert_snapshot,
+    uv_map::UvMap,
+    vec2::Vec2,
+};
+
+fn standing() -> Mat<Color> {
+    import_sprite("sprites/standing.png").unwrap()
+}
+
+#[test]
+fn sprite_flip() {
+    let sprite = standing();
+
+    assert_snapshot("sprite_flip_none", &sprite.slice_flip((false, false)));
+    assert_snapshot("sprite_flip_r", &sprite.slice_flip((true, false)));
+    assert_snapshot("sprite_flip_c", &sprite.slice_flip((false, true)));
+    assert_snapshot("sprite_flip_both", &sprite.slice_flip((true, true)));
+}
+
+#[test]
+fn sprite_rotate() {
+    let sprite = standing();
+
+    assert_snapshot("sprite_rotate_90", &sprite.rotate_90());
+    assert_snapshot("sprite_rotate_180", &sprite.rotate_180());
+    assert_snapshot("sprite_rotate_270", &sprite.rotate_270());
+    assert_snapshot(
+        "sprite_transposed_flip",
+        &sprite.slice_flip((true, false)).transposed(),
+    );
+}
+
+#[test]
+fn uv_map_render() {
+    let map = UvMap::new(import_sprite("textures/uv_map.png").unwrap());
+    let sprite = standing();
+
+    assert_snapshot("uv_map_render", &map.render(&sprite));
+    assert_snapshot(
+        "uv_map_render_flip",
+        &map.render(&sprite.slice_flip((true, false))),
+    );
+
+    let rendered = map.rendered(sprite.as_slice());
+    assert_eq!(rendered.to_mat(), map.render(&sprite));
+    assert_eq!(
+        rendered.rotate_90().to_mat(),
+        map.render(&sprite.rotate_90())
+    );
+    assert_snapshot("uv_map_rendered_rotate_90", &rendered.rotate_90());
+    assert_snapshot("uv_map_render_scale_2", &map.render(&sprite.scale(2)));
+    assert_eq!(
+        rendered.scale(2).rotate_90().to_mat(),
+        map.render(&sprite.scale(2)).rotate_90().to_mat()
+    );
+    assert_eq!(
+        rendered.rotate_90().scale(2).to_mat(),
+        map.render(&sprite.rotate_90().scale(2))
+    );
+}
+
+#[test]
+fn sprite_resize() {
+    let sprite = standing();
+
+    assert_snapshot("sprite_scale_3", &sprite.scale(3));
+    assert_snapshot("sprite_resize", &sprite.resize((40, 30)));
+}
+
+#[test]
+fn image_at_clipping() {
+    let mut headless = GameBuilder::new()
+        .dims(Vec2(32., 16.))
+        .render_dims(Vec2(16., 16.))
+        .render_pos(Vec2(8., 0.))
+        .background_color(Color::BLACK)
+        .build()
+        .headless();
+
+    let mut image = Mat::filled_with(Color::RED, (6, 6));
+    image.fill_with_r(|(x, y)| {
+        if (x + y) % 2 == 0 {
+            Color::WHITE
+        } else {
+            Color::new(0, 0, 255, (x * 40) as u8)
+        }
+    });
+
+    headless.run(1, |game, _| {
+        game.image_at(Vec2(-3., -3.), &image);
+        game.image_at(Vec2(5., 4.), &image);
+        game.image_at(Vec2(20., 12.), &image);
+        game.image_at(Vec2(29., 2.), &image);
+    });
+
+    assert_snapshot("image_at_clipping", &headless.frame());
+}
+
+#[test]
+fn image_at_rotated() {
+    let mut headless = GameBuilder::new()
+        .dims(Vec2(16., 16.))
+        .background_color(Color::BLACK)
+        .build()
+        .headless();
+
+    let mut image = Mat::filled_with(Color::RED, (6, 3));
+    image.fill_with_r(|(x, y)| {
+        if (x + y) % 2 == 0 {
+            Color::WHITE
+        } else {
+            Color::new(0, 0, 255, 255)
+        }
+    });
+    image[(0, 0)] = Color::RED;
+
+    headless.run(1, |game, _| {
+        game.image_at(Vec2(1., 1.), &image.rotate_90());
+        game.image_at(Vec2(6., 1.), &image.rotate_180());
+        game.image_at(Vec2(1., 9.), &image.rotate_270());
+        game.image_at(Vec2(6., 9.), &image.slice_flip((true, false)).transposed());
+    });
+
+    assert_snapshot("image_at_rotated", &headless.frame());
+}
+
+#[test]
+fn image_at_blend() {
+    let mut headless = GameBuilder::new()
+        .dims(Vec2(40., 8.))
+        .background_color(Color::new(100, 120, 140, 255))
+        .build()
+        .headless();
+
+    let mut image = Mat::filled_with(Color::TRANSPARENT, (8, 8));
+    image.fill_with_r(|(x, y)| Color::new(255, (y * 32) as u8, 0, (x * 36) as u8));
+
+    headless.run(1, |game, _| {
+        let modes = [
+            BlendMode::Opaque,
+            BlendMode::Alpha,
+            BlendMode::Additive,
+            BlendMode::Multiply,
+            BlendMode::Screen,
+        ];
+        for (i, mode) in modes.into_iter().enumerate() {
+            game.image_at_blend(Vec2(i as f64 * 8., 0.), &image, mode, 0.8);
+        }
+    });
+
+    assert_snapshot("image_at_blend", &headless.frame());
+}
+
+#[test]
+fn primitives() {
+    let mut headless = GameBuilder::new()
+        .dims(Vec2(48., 32.))
+        .render_dims(Vec2(40., 32.))
+        .render_pos(Vec2(4., 0.))
+        .background_color(Color::BLACK)
+        .build()
+        .headless();
+
+    headless.run(1, |game, _| {
+        let mut painter = game.painter();
+        painter.fill_rect(Vec2(2., 20.), Vec2(20., 16.), Color::new(40, 40, 50, 255));
+        painter.rect(Vec2(6., 2.), Vec2(10., 8.), Color::RED);
+        painter.line(Vec2(0., 31.), Vec2(47., 0.), Color::WHITE);
+        painter.fill_circle(Vec2(30., 8.), 6., Color::new(0, 0, 255, 255));
+        painter.circle(Vec2(30., 8.), 6., Color::WHITE);
+        painter.fill_ellipse(Vec2(36., 24.), Vec2(10., 4.), Color::new(0, 200, 0, 255));
+        painter.ellipse(Vec2(36., 24.), Vec2(10., 4.), Color::WHITE);
+        painter.fill_polygon(
+            &[
+                Vec2(14., 14.),
+                Vec2(24., 18.),
+                Vec2(18., 30.),
+                Vec2(16., 20.),
+            ],
+            Color::new(255, 200, 0, 255),
+        );
+        painter.polygon(&[Vec2(2., 2.), Vec2(4., 12.), Vec2(0., 14.)], Color::RED);
+    });
+
+    assert_snapshot("primitives", &headless.frame());
+}
+
+#[test]
+fn text() {
+    let font = import_font("fonts/small.png", "fonts/small.txt").unwrap();
+    let mut headless = GameBuilder::new()
+        .dims(Vec2(48., 48.))
+        .background_color(Color::new(20, 20, 40, 255))
+        .build()
+        .headless();
+
+    headless.run(1, |game, _| {
+        game.text(Vec2(1., 1.), &font, "Score: 1250", &TextOptions::default());
+        for (i, align) in [Align::Left, Align::Center, Align::Right]
+            .into_iter()
+            .enumerate()
+        {
+            game.text(
+                Vec2(1., 8. + i as f64 * 13.),
+                &font,
+                "Hello, brave new world!",
+                &TextOptions {
+                    color: Color::new(255, 200, 0, 255),
+                    max_width: Some(46),
+                    align,
+                },
+            );
+        }
+    });
+
+    assert_snapshot("text", &headless.frame());
+}
+
+#[test]
+fn layers() {
+    let mut game = GameBuilder::new()
+        .dims(Vec2(32., 16.))
+        .render_dims(Vec2(16., 16.))
+        .render_pos(Vec2(16., 0.))
+        .build();
+
+    let mut background = Layer::new(Vec2(24., 16.), Some(Color::new(20, 20, 60, 255)));
+    *background.z_index_mut() = -1;
+    *background.parallax_mut() = 0.5;
+    for x in (0..24).step_by(4) {
+        background.painter().line(
+            Vec2(x as f64, 0.),
+            Vec2(x as f64, 15.),
+            Color::new(80, 80, 160, 255),
+        );
+    }
+    game.add_layer("background", background);
+
+    let mut ui = Layer::new(Vec2(16., 16.), None);
+    *ui.z_index_mut() = 10;
+    *ui.parallax_mut() = 0.;
+    *ui.opacity_mut() = 0.5;
+    ui.painter()
+        .fill_rect(Vec2(0., 12.), Vec2(16., 4.), Color::RED);
+    game.add_layer("ui", ui);
+
+    let mut hidden = Layer::new(Vec2(32., 16.), Some(Color::WHITE));
+    *hidden.z_index_mut() = 20;
+    *hidden.visible_mut() = false;
+    game.add_layer("hidden", hidden);
+
+    let mut headless = game.headless();
+    headless.run(1, |game, _| {
+        game.painter()
+            .fill_rect(Vec2(0., 8.), Vec2(32., 6.), Color::new(0, 200, 0, 255));
+        game.select_layer("ui");
+        game.painter().fill_circle(Vec2(8., 5.), 3., Color::WHITE);
+    });
+
+    assert_snapshot("layers", &headless.frame());
+}